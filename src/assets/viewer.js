@@ -0,0 +1,481 @@
+"use strict";
+
+(function () {
+  const NODE_WIDTH = 200;
+  const NODE_HEIGHT = 48;
+  const LAYER_GAP = 80;
+  const ROW_GAP = 16;
+  const LINE_LENGTH = 30;
+  const SVG_NS = "http://www.w3.org/2000/svg";
+
+  const data = JSON.parse(document.getElementById("graph-data").textContent);
+  const svg = document.getElementById("graph");
+  const viewport = document.getElementById("viewport");
+  const details = document.getElementById("details");
+  const search = document.getElementById("search");
+  const labelSelect = document.getElementById("labels");
+  // The viewport's translation and scale.
+  const view = { x: 0, y: 0, scale: 1 };
+
+  const nodes = new Map();
+  for (const node of data.nodes) {
+    nodes.set(node.id, { ...node, incoming: [], outgoing: [] });
+  }
+  const edges = data.edges.filter(
+    (edge) => !edge.redundant && nodes.has(edge.from) && nodes.has(edge.to),
+  );
+  for (const edge of edges) {
+    nodes.get(edge.from).outgoing.push(edge);
+    nodes.get(edge.to).incoming.push(edge);
+  }
+
+  layout();
+  render();
+  setUpFilters();
+  fit();
+
+  // Layout
+
+  // Places each node in a column after everything it depends on, then orders
+  // each column to reduce crossings.
+  function layout() {
+    const visiting = new Set();
+    const layerOf = (node) => {
+      if (node.layer !== undefined) {
+        return node.layer;
+      }
+      // Ignore the edge that closes a cycle.
+      if (visiting.has(node.id)) {
+        return 0;
+      }
+      visiting.add(node.id);
+      let layer = 0;
+      for (const edge of node.incoming) {
+        layer = Math.max(layer, layerOf(nodes.get(edge.from)) + 1);
+      }
+      visiting.delete(node.id);
+      node.layer = layer;
+      return layer;
+    };
+    const sparseLayers = [];
+    for (const node of nodes.values()) {
+      const layer = layerOf(node);
+      (sparseLayers[layer] ??= []).push(node);
+    }
+    // Cycles can leave layers empty, e.g. 1 and 2 in a two-item cycle are on
+    // layers 1 and 2, so renumber the others from 0.
+    const layers = sparseLayers.filter((layer) => layer !== undefined);
+    layers.forEach((layer, index) => {
+      for (const node of layer) {
+        node.layer = index;
+      }
+    });
+
+    const setRows = (layer) => layer.forEach((node, row) => (node.row = row));
+    layers.forEach(setRows);
+    const barycenter = (node, neighbors) => {
+      const rows = neighbors
+        .map((id) => nodes.get(id))
+        .filter((neighbor) => neighbor.layer !== node.layer)
+        .map((neighbor) => neighbor.row);
+      return rows.length === 0
+        ? node.row
+        : rows.reduce((sum, row) => sum + row, 0) / rows.length;
+    };
+    for (let sweep = 0; sweep < 4; sweep++) {
+      for (const layer of layers.slice(1)) {
+        sortBy(layer, (node) =>
+          barycenter(node, node.incoming.map((edge) => edge.from)),
+        );
+        setRows(layer);
+      }
+      for (const layer of layers.slice(0, -1).reverse()) {
+        sortBy(layer, (node) =>
+          barycenter(node, node.outgoing.map((edge) => edge.to)),
+        );
+        setRows(layer);
+      }
+    }
+
+    const maxRows = Math.max(0, ...layers.map((layer) => layer.length));
+    layers.forEach((layer, index) => {
+      const offset = ((maxRows - layer.length) * (NODE_HEIGHT + ROW_GAP)) / 2;
+      for (const node of layer) {
+        node.x = index * (NODE_WIDTH + LAYER_GAP);
+        node.y = offset + node.row * (NODE_HEIGHT + ROW_GAP);
+      }
+    });
+  }
+
+  function sortBy(items, key) {
+    const keys = new Map(items.map((item) => [item, key(item)]));
+    items.sort((a, b) => keys.get(a) - keys.get(b));
+  }
+
+  // Rendering
+
+  function render() {
+    for (const edge of edges) {
+      const from = nodes.get(edge.from);
+      const to = nodes.get(edge.to);
+      const x1 = from.x + NODE_WIDTH;
+      const y1 = from.y + NODE_HEIGHT / 2;
+      const x2 = to.x;
+      const y2 = to.y + NODE_HEIGHT / 2;
+      const bend = Math.max(LAYER_GAP / 2, Math.abs(x2 - x1) / 2);
+      edge.element = element("path", {
+        class: edge.kind === "closes" ? "edge closes" : "edge",
+        d: `M ${x1} ${y1} C ${x1 + bend} ${y1}, ${x2 - bend} ${y2}, ${x2} ${y2}`,
+      });
+      viewport.append(edge.element);
+    }
+
+    for (const node of nodes.values()) {
+      const classes = ["node", node.open ? "open" : "closed"];
+      if (node.kind === "pull_request") {
+        classes.push("pull-request");
+      }
+      const group = element("g", {
+        class: classes.join(" "),
+        transform: `translate(${node.x}, ${node.y})`,
+      });
+      const title = element("title");
+      title.textContent = node.text;
+      group.append(
+        title,
+        element("rect", { width: NODE_WIDTH, height: NODE_HEIGHT }),
+      );
+      const lines = wrap(node.text);
+      lines.forEach((line, index) => {
+        const text = element("text", {
+          x: NODE_WIDTH / 2,
+          y: NODE_HEIGHT / 2 + (index - (lines.length - 1) / 2) * 14,
+          "text-anchor": "middle",
+          "dominant-baseline": "middle",
+        });
+        text.textContent = line;
+        group.append(text);
+      });
+      group.addEventListener("click", (event) => {
+        event.stopPropagation();
+        select(node);
+      });
+      node.element = group;
+      viewport.append(group);
+    }
+
+    svg.addEventListener("click", () => select(null));
+    document.addEventListener("keydown", (event) => {
+      if (event.key === "Escape") {
+        select(null);
+      }
+    });
+    setUpPanZoom();
+  }
+
+  // Splits text into at most two lines, with an ellipsis if it's too long.
+  function wrap(text) {
+    const lines = [];
+    let line = "";
+    for (const word of text.split(/\s+/)) {
+      if (line && line.length + word.length + 1 > LINE_LENGTH) {
+        lines.push(line);
+        line = word;
+      } else {
+        line = line ? `${line} ${word}` : word;
+      }
+    }
+    lines.push(line);
+    if (lines.length > 2 || lines[1]?.length > LINE_LENGTH) {
+      lines.length = 2;
+      lines[1] = `${lines[1].slice(0, LINE_LENGTH - 1)}…`;
+    }
+    if (lines[0].length > LINE_LENGTH) {
+      lines[0] = `${lines[0].slice(0, LINE_LENGTH - 1)}…`;
+    }
+    return lines;
+  }
+
+  function element(name, attributes = {}) {
+    const element = document.createElementNS(SVG_NS, name);
+    for (const [key, value] of Object.entries(attributes)) {
+      element.setAttribute(key, value);
+    }
+    return element;
+  }
+
+  // Pan and zoom
+
+  function applyView() {
+    viewport.setAttribute(
+      "transform",
+      `translate(${view.x}, ${view.y}) scale(${view.scale})`,
+    );
+  }
+
+  function fit() {
+    const visible = [...nodes.values()].filter((node) => !node.hidden);
+    if (visible.length === 0) {
+      return;
+    }
+    const left = Math.min(...visible.map((node) => node.x));
+    const top = Math.min(...visible.map((node) => node.y));
+    const right = Math.max(...visible.map((node) => node.x + NODE_WIDTH));
+    const bottom = Math.max(...visible.map((node) => node.y + NODE_HEIGHT));
+    const { width, height } = svg.getBoundingClientRect();
+    const margin = 24;
+    view.scale = Math.min(
+      1.5,
+      (width - 2 * margin) / (right - left),
+      (height - 2 * margin) / (bottom - top),
+    );
+    view.x = (width - (right - left) * view.scale) / 2 - left * view.scale;
+    view.y = (height - (bottom - top) * view.scale) / 2 - top * view.scale;
+    applyView();
+  }
+
+  function center(node) {
+    const { width, height } = svg.getBoundingClientRect();
+    view.scale = Math.max(view.scale, 1);
+    view.x = width / 2 - (node.x + NODE_WIDTH / 2) * view.scale;
+    view.y = height / 2 - (node.y + NODE_HEIGHT / 2) * view.scale;
+    applyView();
+  }
+
+  function setUpPanZoom() {
+    svg.addEventListener(
+      "wheel",
+      (event) => {
+        event.preventDefault();
+        const { left, top } = svg.getBoundingClientRect();
+        const x = event.clientX - left;
+        const y = event.clientY - top;
+        const factor = Math.exp(-event.deltaY * 0.002);
+        const scale = Math.min(4, Math.max(0.05, view.scale * factor));
+        // Keep the point under the pointer still.
+        view.x = x - ((x - view.x) * scale) / view.scale;
+        view.y = y - ((y - view.y) * scale) / view.scale;
+        view.scale = scale;
+        applyView();
+      },
+      { passive: false },
+    );
+
+    let drag = null;
+    svg.addEventListener("pointerdown", (event) => {
+      drag = { x: event.clientX, y: event.clientY, moved: false };
+    });
+    svg.addEventListener("pointermove", (event) => {
+      if (!drag) {
+        return;
+      }
+      const dx = event.clientX - drag.x;
+      const dy = event.clientY - drag.y;
+      if (!drag.moved && Math.hypot(dx, dy) < 4) {
+        return;
+      }
+      if (!drag.moved) {
+        drag.moved = true;
+        svg.setPointerCapture(event.pointerId);
+        svg.classList.add("panning");
+      }
+      view.x += dx;
+      view.y += dy;
+      drag.x = event.clientX;
+      drag.y = event.clientY;
+      applyView();
+    });
+    const endDrag = (event) => {
+      if (drag?.moved) {
+        // Don't treat the end of a drag as a click.
+        svg.addEventListener("click", (click) => click.stopPropagation(), {
+          capture: true,
+          once: true,
+        });
+        svg.releasePointerCapture(event.pointerId);
+      }
+      svg.classList.remove("panning");
+      drag = null;
+    };
+    svg.addEventListener("pointerup", endDrag);
+    svg.addEventListener("pointercancel", endDrag);
+    document.getElementById("fit").addEventListener("click", fit);
+  }
+
+  // Highlighting
+
+  // Returns the IDs of the nodes reachable from the node, not including it.
+  function reachable(node, next) {
+    const found = new Set();
+    const stack = [node];
+    while (stack.length !== 0) {
+      for (const id of next(stack.pop())) {
+        if (!found.has(id) && id !== node.id) {
+          found.add(id);
+          stack.push(nodes.get(id));
+        }
+      }
+    }
+    return found;
+  }
+
+  function select(node) {
+    for (const other of nodes.values()) {
+      other.element.classList.remove("selected", "ancestor", "descendant");
+    }
+    for (const edge of edges) {
+      edge.element.classList.remove("highlighted");
+    }
+    svg.classList.toggle("focused", node !== null);
+    if (node === null) {
+      details.hidden = true;
+      return;
+    }
+
+    const ancestors = reachable(node, (current) =>
+      current.incoming.map((edge) => edge.from),
+    );
+    const descendants = reachable(node, (current) =>
+      current.outgoing.map((edge) => edge.to),
+    );
+    node.element.classList.add("selected");
+    for (const id of ancestors) {
+      nodes.get(id).element.classList.add("ancestor");
+    }
+    for (const id of descendants) {
+      nodes.get(id).element.classList.add("descendant");
+    }
+    // Highlight the paths into and out of the node.
+    const upstream = new Set([node.id, ...ancestors]);
+    const downstream = new Set([node.id, ...descendants]);
+    for (const edge of edges) {
+      edge.element.classList.toggle(
+        "highlighted",
+        (upstream.has(edge.from) && upstream.has(edge.to)) ||
+          (downstream.has(edge.from) && downstream.has(edge.to)),
+      );
+    }
+    showDetails(node, ancestors.size, descendants.size);
+  }
+
+  function showDetails(node, ancestorCount, descendantCount) {
+    details.replaceChildren();
+    const heading = document.createElement("h2");
+    if (node.url) {
+      const link = document.createElement("a");
+      link.href = node.url;
+      link.target = "_blank";
+      link.rel = "noopener";
+      link.textContent = node.text;
+      heading.append(link);
+    } else {
+      heading.textContent = node.text;
+    }
+    details.append(heading);
+
+    const facts = [
+      [node.open ? "Open" : "Closed", ""],
+      [`Blocked by ${ancestorCount}`, "ancestor"],
+      [`Blocks ${descendantCount}`, "descendant"],
+    ];
+    const summary = document.createElement("p");
+    facts.forEach(([text, className], index) => {
+      const span = document.createElement("span");
+      span.className = className;
+      span.textContent = text;
+      summary.append(index === 0 ? "" : " · ", span);
+    });
+    details.append(summary);
+
+    for (const [name, values] of [
+      ["Labels", node.labels],
+      ["Projects", node.projects],
+    ]) {
+      if (values.length !== 0) {
+        const paragraph = document.createElement("p");
+        paragraph.textContent = `${name}: ${values.join(", ")}`;
+        details.append(paragraph);
+      }
+    }
+    details.hidden = false;
+  }
+
+  // Search and filters
+
+  function matches(node, query) {
+    const number = query.match(/^#(\d+)$/);
+    if (number) {
+      return new RegExp(`/${number[1]}$`).test(node.url);
+    }
+    return (
+      node.text.toLowerCase().includes(query) ||
+      node.url.toLowerCase().includes(query)
+    );
+  }
+
+  function setUpFilters() {
+    const labels = new Set(data.nodes.flatMap((node) => node.labels));
+    for (const label of [...labels].sort()) {
+      const option = document.createElement("option");
+      option.value = label;
+      option.textContent = label;
+      labelSelect.append(option);
+    }
+
+    search.addEventListener("input", () => {
+      const query = search.value.trim().toLowerCase();
+      svg.classList.toggle("searching", query !== "");
+      for (const node of nodes.values()) {
+        node.element.classList.toggle(
+          "match",
+          query !== "" && matches(node, query),
+        );
+      }
+    });
+    search.addEventListener("keydown", (event) => {
+      if (event.key !== "Enter") {
+        return;
+      }
+      const query = search.value.trim().toLowerCase();
+      const match = [...nodes.values()].find(
+        (node) => !node.hidden && query !== "" && matches(node, query),
+      );
+      if (match) {
+        select(match);
+        center(match);
+      }
+    });
+
+    const filters = ["show-open", "show-closed", "show-pull-requests"].map(
+      (id) => document.getElementById(id),
+    );
+    for (const input of [...filters, labelSelect]) {
+      input.addEventListener("change", applyFilters);
+    }
+  }
+
+  function applyFilters() {
+    const showOpen = document.getElementById("show-open").checked;
+    const showClosed = document.getElementById("show-closed").checked;
+    const showPullRequests =
+      document.getElementById("show-pull-requests").checked;
+    const labels = [...labelSelect.selectedOptions].map(
+      (option) => option.value,
+    );
+
+    for (const node of nodes.values()) {
+      node.hidden =
+        (node.open ? !showOpen : !showClosed) ||
+        (node.kind === "pull_request" && !showPullRequests) ||
+        (labels.length !== 0 &&
+          !node.labels.some((label) => labels.includes(label)));
+      node.element.classList.toggle("hidden", node.hidden);
+    }
+    for (const edge of edges) {
+      edge.element.classList.toggle(
+        "hidden",
+        nodes.get(edge.from).hidden || nodes.get(edge.to).hidden,
+      );
+    }
+  }
+})();
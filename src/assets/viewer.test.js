@@ -0,0 +1,124 @@
+"use strict";
+
+// Runs viewer.js against a minimal fake DOM.  Run with `node --test`.
+
+const assert = require("node:assert");
+const fs = require("node:fs");
+const path = require("node:path");
+const test = require("node:test");
+const vm = require("node:vm");
+
+const SCRIPT = fs.readFileSync(path.join(__dirname, "viewer.js"), "utf8");
+
+class FakeElement {
+  constructor(name) {
+    this.name = name;
+    this.attributes = {};
+    this.children = [];
+    this.classList = {
+      add() {},
+      remove() {},
+      toggle() {},
+    };
+    this.textContent = "";
+    this.checked = true;
+    this.selectedOptions = [];
+  }
+
+  setAttribute(name, value) {
+    this.attributes[name] = String(value);
+  }
+
+  append(...children) {
+    this.children.push(...children);
+  }
+
+  replaceChildren() {
+    this.children = [];
+  }
+
+  addEventListener() {}
+
+  getBoundingClientRect() {
+    return { left: 0, top: 0, width: 800, height: 600 };
+  }
+}
+
+// Runs the viewer on the graph and returns each node's position by ID.
+function layOut(ids, edges) {
+  const elements = new Map();
+  const document = {
+    getElementById(id) {
+      if (!elements.has(id)) {
+        elements.set(id, new FakeElement(id));
+      }
+      return elements.get(id);
+    },
+    createElement: (name) => new FakeElement(name),
+    createElementNS: (_, name) => new FakeElement(name),
+    addEventListener() {},
+  };
+  document.getElementById("graph-data").textContent = JSON.stringify({
+    title: "",
+    nodes: ids.map((id) => ({
+      id,
+      kind: "issue",
+      text: `Issue ${id}`,
+      url: "",
+      open: true,
+      labels: [],
+      projects: [],
+      repository: null,
+      group: null,
+    })),
+    edges: edges.map(([from, to]) => ({ from, to, kind: "depends_on" })),
+  });
+
+  vm.runInNewContext(SCRIPT, { document });
+
+  const positions = {};
+  for (const child of document.getElementById("viewport").children) {
+    if (child.name !== "g") {
+      continue;
+    }
+    const [, x, y] = child.attributes.transform.match(
+      /^translate\((.*), (.*)\)$/,
+    );
+    positions[child.children[0].textContent.replace("Issue ", "")] = {
+      x: Number(x),
+      y: Number(y),
+    };
+  }
+  // Not set if there's nothing to fit.
+  const transform =
+    document.getElementById("viewport").attributes.transform ?? "";
+  assert.doesNotMatch(transform, /NaN|Infinity/);
+  return positions;
+}
+
+test("dependencies are in later columns", () => {
+  const positions = layOut(["1", "2", "3"], [
+    ["1", "2"],
+    ["2", "3"],
+  ]);
+  assert.deepStrictEqual(
+    ["1", "2", "3"].map((id) => positions[id].x),
+    [0, 280, 560],
+  );
+});
+
+test("a graph that's only a cycle starts in the first column", () => {
+  const positions = layOut(["1", "2"], [
+    ["1", "2"],
+    ["2", "1"],
+  ]);
+  const xs = Object.values(positions).map((position) => position.x);
+  assert.deepStrictEqual(xs.sort(), [0, 280]);
+  for (const { y } of Object.values(positions)) {
+    assert.strictEqual(y, 0);
+  }
+});
+
+test("an empty graph", () => {
+  assert.deepStrictEqual(layOut([], []), {});
+});
@@ -1,48 +1,51 @@
+use std::cell::OnceCell;
+use std::collections::{HashMap, HashSet, VecDeque};
+
 use indexmap::{IndexMap, IndexSet};
-use time::OffsetDateTime;
+use time::{Duration, OffsetDateTime};
 
+use crate::clock::{Clock, SystemClock};
+use crate::error::GraphError;
+use crate::forecast::{estimate_duration, Estimates};
+use crate::gantt::{
+    Gantt, GanttEnd, GanttSection, GanttStart, GanttStatus, GanttTask,
+};
 use crate::github::GithubIssueState;
+use crate::graph::{Graph, Node, NodeId, NodeKind};
+use crate::order::WorkOrder;
 
-pub(crate) type NodeId = String;
-
+/// Decides which nodes to draw.
 #[derive(Debug)]
-pub(crate) struct Node {
-    pub id: NodeId,
-    pub text: String,
-    pub url: String,
-    pub state: GithubIssueState,
-    #[allow(unused)]
-    pub labels: Vec<String>,
-    pub project_titles: IndexSet<String>,
-    pub depends_on_urls: IndexSet<String>,
-    pub blocks_count: u32,
-    pub updated_at: OffsetDateTime,
+pub struct Filter {
+    /// Only include items in the project with this title.
+    pub include_project_only: Option<String>,
+    /// Also include closed items that were updated within this long before
+    /// now.
+    pub closed_within: Option<Duration>,
+    /// The source of "now".
+    pub clock: Box<dyn Clock>,
 }
 
-impl Node {
-    fn is_open(&self) -> bool {
-        match self.state {
-            GithubIssueState::Open => true,
-            GithubIssueState::Closed => false,
+impl Default for Filter {
+    fn default() -> Self {
+        Self {
+            include_project_only: None,
+            closed_within: None,
+            clock: Box::new(SystemClock),
         }
     }
-
-    /// Returns true if this node should be included in the flowchart.
-    fn passes_filter(&self, filter: &Filter) -> bool {
-        filter.matches_project(&self.project_titles)
-            && (self.is_open()
-                || filter.matches_updated_after(&self.updated_at))
-            && (!self.depends_on_urls.is_empty() || self.blocks_count != 0)
-    }
-}
-
-#[derive(Debug)]
-pub(crate) struct Filter {
-    include_project_only: Option<String>,
-    updated_after: Option<OffsetDateTime>,
 }
 
 impl Filter {
+    /// Returns true if the node should be included in the flowchart.
+    fn matches(&self, node: &Node) -> bool {
+        self.matches_project(&node.project_titles)
+            && (node.is_open() || self.matches_updated_after(node.updated_at))
+            && (!node.depends_on_urls.is_empty()
+                || !node.closes_urls.is_empty()
+                || node.blocks_count != 0)
+    }
+
     fn matches_project(&self, project_titles: &IndexSet<String>) -> bool {
         self.include_project_only
             .as_ref()
@@ -50,106 +53,1070 @@ impl Filter {
             .unwrap_or(true)
     }
 
-    fn matches_updated_after(&self, updated_at: &OffsetDateTime) -> bool {
-        self.updated_after
-            .map(|updated_after| *updated_at >= updated_after)
-            .unwrap_or(false)
+    /// Returns false if when the item was updated is unknown.
+    fn matches_updated_after(
+        &self,
+        updated_at: Option<OffsetDateTime>,
+    ) -> bool {
+        let (Some(within), Some(updated_at)) = (self.closed_within, updated_at)
+        else {
+            return false;
+        };
+        updated_at >= self.clock.now() - within
     }
 }
 
+/// Renders a [`Graph`] as a Mermaid flowchart.
 #[derive(Debug)]
-pub(crate) struct Flowchart {
+pub struct Flowchart<'g> {
     title: String,
-    pub nodes: IndexMap<NodeId, Node>,
+    graph: &'g Graph,
     show_all: bool,
     filter: Filter,
+    pub group_by: GroupBy,
+    pub style: Style,
+    pub limits: Limits,
+    /// Don't draw dependencies that are implied by others, i.e. draw the
+    /// transitive reduction.
+    pub reduce: bool,
+    /// Redundant dependencies, as (prerequisite ID, dependent ID).
+    redundant: OnceCell<HashSet<(NodeId, NodeId)>>,
+}
+
+/// How to group nodes into subgraphs.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupBy {
+    #[default]
+    None,
+    /// Group by the repository the item is in.
+    Repository,
+    /// Group by the first project the item is in.
+    Project,
+    /// Group by the milestone the item is in.
+    Milestone,
+}
+
+impl GroupBy {
+    /// Returns the name of the group the node belongs to, if any.
+    fn key(self, node: &Node) -> Option<&str> {
+        match self {
+            GroupBy::None => None,
+            GroupBy::Repository => node.repository_name(),
+            GroupBy::Project => node.project_titles.first().map(String::as_str),
+            GroupBy::Milestone => node.milestone.as_deref(),
+        }
+    }
 }
 
-impl Flowchart {
+impl std::str::FromStr for GroupBy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(GroupBy::None),
+            "repository" => Ok(GroupBy::Repository),
+            "project" => Ok(GroupBy::Project),
+            "milestone" => Ok(GroupBy::Milestone),
+            _ => Err(format!(
+                "Unexpected value {s:?}; expected none, repository, project, or milestone"
+            )),
+        }
+    }
+}
+
+/// Mermaid styles for node classes, e.g. "stroke:#317236,stroke-width:8px".
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Style {
+    pub open: String,
+    pub closed: String,
+    /// Additional styles for nodes with the given labels.
+    pub labels: IndexMap<String, String>,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            // Green border.
+            open: "stroke:#317236,stroke-width:8px".to_owned(),
+            // Purple border. Gray text.
+            closed: "stroke:#7048D4,stroke-width:8px,color:#636871".to_owned(),
+            labels: IndexMap::new(),
+        }
+    }
+}
+
+impl<'g> Flowchart<'g> {
     pub fn new(
+        graph: &'g Graph,
         title: String,
         show_all: bool,
-        include_project_only: Option<String>,
-        updated_after: Option<OffsetDateTime>,
+        filter: Filter,
     ) -> Self {
-        let filter = Filter {
-            updated_after,
-            include_project_only,
-        };
-
         Self {
             title,
-            nodes: IndexMap::default(),
+            graph,
             show_all,
             filter,
+            group_by: GroupBy::default(),
+            style: Style::default(),
+            limits: Limits::default(),
+            reduce: false,
+            redundant: OnceCell::new(),
         }
     }
 }
 
-impl std::fmt::Display for Flowchart {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if !self.title.is_empty() {
-            writeln!(f, "---\ntitle:{}\n---", self.title)?;
+impl Flowchart<'_> {
+    /// Returns true if the node should be drawn.  Pull requests are only drawn
+    /// when an issue they close is drawn.
+    fn is_visible(&self, node: &Node) -> bool {
+        if !self.show_all && !self.filter.matches(node) {
+            return false;
         }
-        writeln!(f, "flowchart LR")?;
-        // Purple border. Gray text.
-        writeln!(
-            f,
-            "  classDef state-closed stroke:#7048D4,stroke-width:8px,color:#636871"
-        )?;
-        // Green border.
-        writeln!(f, "  classDef state-open stroke:#317236,stroke-width:8px")?;
-
-        for node in self.nodes.values() {
-            // Does it pass the filter?
-            if !self.show_all && !node.passes_filter(&self.filter) {
+
+        match node.kind {
+            NodeKind::Issue => true,
+            NodeKind::PullRequest => node.closes_urls.iter().any(|url| {
+                self.graph.nodes.get(url.as_str()).is_some_and(|issue| {
+                    issue.kind == NodeKind::Issue
+                        && (self.show_all || self.filter.matches(issue))
+                })
+            }),
+        }
+    }
+}
+
+/// The nodes and edges that a [`Flowchart`] draws, for renderers other than
+/// Mermaid, e.g. JSON or HTML.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ChartData {
+    pub title: String,
+    pub nodes: Vec<ChartNode>,
+    pub edges: Vec<ChartEdge>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ChartNode {
+    pub id: String,
+    pub kind: NodeKind,
+    pub text: String,
+    pub url: String,
+    pub open: bool,
+    pub labels: Vec<String>,
+    pub projects: Vec<String>,
+    pub repository: Option<String>,
+    /// The subgraph the node is in, depending on [`GroupBy`].
+    pub group: Option<String>,
+}
+
+/// An arrow from `from` to `to`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ChartEdge {
+    pub from: String,
+    pub to: String,
+    pub kind: EdgeKind,
+    /// The dependency is implied by others and isn't drawn.  Only set when
+    /// reducing.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub redundant: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeKind {
+    /// `from` blocks `to`.
+    DependsOn,
+    /// Merging the pull request `from` closes the issue `to`.
+    Closes,
+}
+
+impl Flowchart<'_> {
+    /// Returns the nodes and edges that would be drawn.
+    pub fn data(&self) -> ChartData {
+        let mut data = ChartData {
+            title: self.title.clone(),
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        };
+
+        for node in self.graph.nodes.values() {
+            if !self.is_visible(node) {
+                continue;
+            }
+            data.nodes.push(ChartNode {
+                id: node.id.clone(),
+                kind: node.kind,
+                text: node.text.clone(),
+                url: node.link().unwrap_or_default().to_owned(),
+                open: node.is_open(),
+                labels: node.labels.clone(),
+                projects: node.project_titles.iter().cloned().collect(),
+                repository: node.repository_name().map(str::to_owned),
+                group: self.group_by.key(node).map(str::to_owned),
+            });
+            self.visit_edges(node, true, |from, to, kind| {
+                data.edges.push(ChartEdge {
+                    from: from.id.clone(),
+                    to: to.id.clone(),
+                    kind,
+                    redundant: self.is_redundant(from, to, kind),
+                });
+            });
+        }
+
+        data
+    }
+
+    /// Returns the visible issues as a gantt chart.  Closed issues are done
+    /// from when they were created until they closed.  Open issues start now,
+    /// or after their open prerequisites, and take as long as their estimate
+    /// label says, or a day.  Sections are the groups, or milestones and then
+    /// repositories when not grouping.  Fails if there are dependency cycles
+    /// among the visible open issues, since those can never start.
+    pub fn gantt(&self) -> Result<Gantt, GraphError> {
+        WorkOrder::with_filter(self.graph, |node| self.is_visible(node))?;
+
+        let now = self.filter.clock.now();
+        let estimates = Estimates::new(1.0);
+        let task_id = |node: &Node| format!("t{}", node.id);
+        let mut sections: IndexMap<&str, Vec<GanttTask>> = IndexMap::new();
+        for node in self.graph.nodes.values() {
+            if node.kind != NodeKind::Issue || !self.is_visible(node) {
                 continue;
             }
 
-            write!(f, "  {}", node.id)?;
-            if !node.text.is_empty() {
-                write!(f, "({})", mermaid_quote(&node.text))?;
+            let (status, start, end) = if node.is_open() {
+                let after: Vec<String> = self
+                    .graph
+                    .dependencies(node)
+                    .filter(|prerequisite| {
+                        prerequisite.kind == NodeKind::Issue
+                            && prerequisite.is_open()
+                            && self.is_visible(prerequisite)
+                    })
+                    .map(task_id)
+                    .collect();
+                let start = if after.is_empty() {
+                    GanttStart::At(now)
+                } else {
+                    GanttStart::After(after)
+                };
+                // The estimates that `get` returns and the default are valid.
+                let days = estimates.get(node).unwrap_or(estimates.default);
+                let end = GanttEnd::Duration(
+                    estimate_duration(days).unwrap_or(Duration::DAY),
+                );
+                (None, start, end)
+            } else {
+                let closed_at =
+                    node.closed_at.or(node.updated_at).unwrap_or(now);
+                let created_at = node
+                    .created_at
+                    .filter(|&created_at| created_at < closed_at)
+                    .unwrap_or(closed_at - Duration::DAY);
+                (
+                    Some(GanttStatus::Done),
+                    GanttStart::At(created_at),
+                    GanttEnd::At(closed_at),
+                )
+            };
+
+            let section = match self.group_by {
+                GroupBy::None => {
+                    node.milestone.as_deref().or(node.repository_name())
+                }
+                group_by => group_by.key(node),
+            };
+            sections
+                .entry(section.unwrap_or("Other"))
+                .or_default()
+                .push(GanttTask {
+                    id: task_id(node),
+                    name: node.text.clone(),
+                    status,
+                    start,
+                    end,
+                    url: node.link().map(str::to_owned),
+                });
+        }
+
+        Ok(Gantt {
+            title: self.title.clone(),
+            sections: sections
+                .into_iter()
+                .map(|(name, tasks)| GanttSection {
+                    name: name.to_owned(),
+                    tasks,
+                })
+                .collect(),
+        })
+    }
+
+    /// Calls `f` with each visible edge out of or into the node that's drawn
+    /// with it.
+    fn for_each_edge<'a>(
+        &'a self,
+        node: &'a Node,
+        f: impl FnMut(&'a Node, &'a Node, EdgeKind),
+    ) {
+        self.visit_edges(node, false, f);
+    }
+
+    /// Like [`Self::for_each_edge`], optionally including redundant edges.
+    fn visit_edges<'a>(
+        &'a self,
+        node: &'a Node,
+        include_redundant: bool,
+        mut f: impl FnMut(&'a Node, &'a Node, EdgeKind),
+    ) {
+        for prerequisite in self.prerequisites(node) {
+            if include_redundant
+                || !self.is_redundant(prerequisite, node, EdgeKind::DependsOn)
+            {
+                f(prerequisite, node, EdgeKind::DependsOn);
+            }
+        }
+        for closes_url in &node.closes_urls {
+            if let Some(issue) = self.graph.nodes.get(closes_url.as_str()) {
+                if self.is_visible(issue) {
+                    f(node, issue, EdgeKind::Closes);
+                }
+            }
+        }
+    }
+
+    /// Returns the visible nodes that the node depends on.
+    fn prerequisites<'a>(
+        &'a self,
+        node: &'a Node,
+    ) -> impl Iterator<Item = &'a Node> + 'a {
+        self.graph
+            .dependencies(node)
+            .filter(|prerequisite| self.is_visible(prerequisite))
+    }
+
+    fn is_redundant(&self, from: &Node, to: &Node, kind: EdgeKind) -> bool {
+        self.reduce
+            && kind == EdgeKind::DependsOn
+            && self
+                .redundant
+                .get_or_init(|| self.redundant_dependencies())
+                .contains(&(from.id.clone(), to.id.clone()))
+    }
+
+    /// Finds dependencies between visible nodes that are implied by a longer
+    /// path.  When A depends on B and C, and B depends on C, then A depending
+    /// on C is redundant.  Dependencies in a cycle aren't redundant, since
+    /// the cycle would be broken.
+    fn redundant_dependencies(&self) -> HashSet<(NodeId, NodeId)> {
+        let mut redundant = HashSet::new();
+        for node in self.graph.nodes.values() {
+            if !self.is_visible(node) {
+                continue;
+            }
+            let prerequisites: Vec<&Node> = self.prerequisites(node).collect();
+            if prerequisites.len() < 2 {
+                continue;
             }
-            writeln!(f)?;
-            match node.state {
-                GithubIssueState::Open => {
-                    writeln!(f, "  class {} state-open", node.id)?;
+            let reachable: Vec<HashSet<&str>> = prerequisites
+                .iter()
+                .map(|prerequisite| self.transitive_prerequisites(prerequisite))
+                .collect();
+            for (c, c_reachable) in prerequisites.iter().zip(&reachable) {
+                let implied = prerequisites.iter().zip(&reachable).any(
+                    |(b, b_reachable)| {
+                        b.id != c.id
+                            && b_reachable.contains(c.id.as_str())
+                            && !c_reachable.contains(b.id.as_str())
+                    },
+                );
+                if implied {
+                    redundant.insert((c.id.clone(), node.id.clone()));
                 }
-                GithubIssueState::Closed => {
-                    writeln!(f, "  class {} state-closed", node.id)?;
+            }
+        }
+        redundant
+    }
+
+    /// Returns the IDs of the visible nodes that the node depends on,
+    /// directly or indirectly.
+    fn transitive_prerequisites<'a>(
+        &'a self,
+        node: &'a Node,
+    ) -> HashSet<&'a str> {
+        let mut found = HashSet::new();
+        let mut stack = vec![node];
+        while let Some(current) = stack.pop() {
+            for prerequisite in self.prerequisites(current) {
+                if found.insert(prerequisite.id.as_str()) {
+                    stack.push(prerequisite);
                 }
             }
-            if !node.url.is_empty() {
+        }
+        found
+    }
+}
+
+impl std::fmt::Display for Flowchart<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Does it pass the filter?
+        let visible_nodes: Vec<&Node> = self
+            .graph
+            .nodes
+            .values()
+            .filter(|node| self.is_visible(node))
+            .collect();
+
+        self.fmt_diagram(f, &visible_nodes, None)
+    }
+}
+
+/// Where a diagram is in a split flowchart.
+#[derive(Debug, Clone, Copy)]
+struct PartInfo<'a> {
+    index: usize,
+    count: usize,
+    /// The index of the part each visible node is in, by node ID.
+    part_of: &'a HashMap<&'a str, usize>,
+}
+
+impl Flowchart<'_> {
+    /// Writes a diagram of the nodes.  For a part of a split flowchart, edges
+    /// to nodes in other parts are drawn to stubs that name the other part.
+    fn fmt_diagram(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        nodes: &[&Node],
+        part: Option<PartInfo<'_>>,
+    ) -> std::fmt::Result {
+        let is_split = part.is_some_and(|part| part.count > 1);
+        match part {
+            Some(part) if is_split && !self.title.is_empty() => {
                 writeln!(
                     f,
-                    "  click {} {}",
-                    node.id,
-                    mermaid_quote(&node.url)
+                    "---\ntitle:{} ({}/{})\n---",
+                    self.title,
+                    part.index + 1,
+                    part.count
                 )?;
             }
-            if !node.depends_on_urls.is_empty() {
-                for depends_on_url in &node.depends_on_urls {
-                    if let Some(prerequisite) =
-                        self.nodes.get(depends_on_url.as_str())
-                    {
-                        if self.show_all
-                            || prerequisite.passes_filter(&self.filter)
-                        {
-                            writeln!(
-                                f,
-                                "  {} --> {}",
-                                prerequisite.id, node.id
-                            )?;
-                        }
+            _ if !self.title.is_empty() => {
+                writeln!(f, "---\ntitle:{}\n---", self.title)?;
+            }
+            _ => {}
+        }
+        writeln!(f, "flowchart LR")?;
+        writeln!(f, "  classDef state-closed {}", self.style.closed)?;
+        writeln!(f, "  classDef state-open {}", self.style.open)?;
+        for (index, style) in self.style.labels.values().enumerate() {
+            writeln!(f, "  classDef label-{index} {style}")?;
+        }
+        if is_split {
+            writeln!(f, "  classDef stub stroke-dasharray:5 5")?;
+        }
+
+        if self.group_by == GroupBy::None {
+            for node in nodes {
+                self.fmt_node(f, node)?;
+                self.fmt_edges(f, node)?;
+            }
+        } else {
+            // Nodes must be declared inside their subgraph, so edges come
+            // after all the subgraphs.
+            let mut groups: IndexMap<Option<&str>, Vec<&Node>> =
+                IndexMap::new();
+            for node in nodes {
+                groups
+                    .entry(self.group_by.key(node))
+                    .or_default()
+                    .push(node);
+            }
+            for (index, (group, nodes)) in groups.iter().enumerate() {
+                if let Some(name) = group {
+                    writeln!(
+                        f,
+                        "  subgraph group{index} [{}]",
+                        mermaid_quote(name)
+                    )?;
+                }
+                for node in nodes {
+                    self.fmt_node(f, node)?;
+                }
+                if group.is_some() {
+                    writeln!(f, "  end")?;
+                }
+            }
+            for node in groups.values().flatten() {
+                self.fmt_edges(f, node)?;
+            }
+        }
+
+        match part {
+            Some(part) if is_split => self.fmt_stubs(f, nodes, part),
+            _ => Ok(()),
+        }
+    }
+
+    /// Writes the edges between the part's nodes and nodes in other parts
+    /// that weren't written with the part's nodes, and the stubs that stand
+    /// in for the other nodes.
+    fn fmt_stubs(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        nodes: &[&Node],
+        part: PartInfo<'_>,
+    ) -> std::fmt::Result {
+        let in_part = |node: &Node| {
+            part.part_of.get(node.id.as_str()) == Some(&part.index)
+        };
+        let mut stubs: IndexMap<&str, &Node> = IndexMap::new();
+        for node in nodes {
+            self.for_each_edge(node, |from, to, _| {
+                for end in [from, to] {
+                    if !in_part(end) {
+                        stubs.insert(&end.id, end);
                     }
                 }
+            });
+        }
+
+        let mut result = Ok(());
+        for owner in self.graph.nodes.values() {
+            if in_part(owner) || !part.part_of.contains_key(owner.id.as_str()) {
+                continue;
+            }
+            self.for_each_edge(owner, |from, to, kind| {
+                if !in_part(from) && !in_part(to) {
+                    return;
+                }
+                stubs.insert(&owner.id, owner);
+                let arrow = match kind {
+                    EdgeKind::DependsOn => "-->",
+                    EdgeKind::Closes => "-.->",
+                };
+                if result.is_ok() {
+                    result = writeln!(f, "  {} {arrow} {}", from.id, to.id);
+                }
+            });
+        }
+        result?;
+
+        for (id, node) in stubs {
+            let other_part = part.part_of.get(id).copied().unwrap_or_default();
+            let text = format!("{} (diagram {})", node.text, other_part + 1);
+            writeln!(f, "  {id}[[{}]]", mermaid_quote(&text))?;
+            writeln!(f, "  class {id} stub")?;
+            if let Some(link) = node.link() {
+                writeln!(f, "  click {id} {}", mermaid_quote(link))?;
             }
         }
         Ok(())
     }
 }
 
+/// Limits on the size of each Mermaid diagram.  GitHub doesn't render
+/// diagrams past a certain size.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Limits {
+    /// The most nodes in a diagram, including stubs for nodes in other
+    /// diagrams.
+    pub max_nodes: Option<usize>,
+    /// The most edges in a diagram.
+    pub max_edges: Option<usize>,
+}
+
+impl Limits {
+    fn fits(&self, (nodes, edges): (usize, usize)) -> bool {
+        self.max_nodes.map_or(true, |max| nodes <= max)
+            && self.max_edges.map_or(true, |max| edges <= max)
+    }
+}
+
+/// A flowchart split into diagrams that fit within its [`Limits`].
+#[derive(Debug)]
+pub struct SplitFlowchart<'a> {
+    flowchart: &'a Flowchart<'a>,
+    parts: Vec<Vec<&'a Node>>,
+    part_of: HashMap<&'a str, usize>,
+}
+
+/// One diagram of a [`SplitFlowchart`].
+#[derive(Debug, Clone, Copy)]
+pub struct FlowchartPart<'a> {
+    split: &'a SplitFlowchart<'a>,
+    index: usize,
+}
+
+impl<'g> Flowchart<'g> {
+    /// Splits the flowchart into diagrams along island boundaries, or
+    /// subgraph boundaries when grouping, so that each fits within the
+    /// limits.  Islands or subgraphs that are too big are split further.
+    pub fn split(&self) -> SplitFlowchart<'_> {
+        let nodes: Vec<&Node> = self
+            .graph
+            .nodes
+            .values()
+            .filter(|node| self.is_visible(node))
+            .collect();
+        let indexes: HashMap<&str, usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| (node.id.as_str(), index))
+            .collect();
+        // Neighbors of each node, with the index of the edge to them.
+        let mut neighbors = vec![Vec::new(); nodes.len()];
+        let mut edge_count = 0;
+        for node in &nodes {
+            self.for_each_edge(node, |from, to, _| {
+                let from = indexes[from.id.as_str()];
+                let to = indexes[to.id.as_str()];
+                neighbors[from].push((to, edge_count));
+                neighbors[to].push((from, edge_count));
+                edge_count += 1;
+            });
+        }
+
+        let units: Vec<Vec<usize>> = if self.group_by == GroupBy::None {
+            islands(&neighbors)
+        } else {
+            let mut groups: IndexMap<Option<&str>, Vec<usize>> =
+                IndexMap::new();
+            for (index, node) in nodes.iter().enumerate() {
+                groups
+                    .entry(self.group_by.key(node))
+                    .or_default()
+                    .push(index);
+            }
+            groups.into_values().collect()
+        };
+
+        let fits = |members: &[usize]| {
+            self.limits.fits(diagram_size(members, &neighbors))
+        };
+        let mut parts: Vec<Vec<usize>> = Vec::new();
+        let mut current: Vec<usize> = Vec::new();
+        for unit in units {
+            let candidate = [current.as_slice(), &unit].concat();
+            if fits(&candidate) {
+                current = candidate;
+                continue;
+            }
+            if !current.is_empty() {
+                parts.push(std::mem::take(&mut current));
+            }
+            if fits(&unit) {
+                current = unit;
+                continue;
+            }
+            // Fill diagrams with neighboring nodes.
+            for index in breadth_first(&unit, &neighbors) {
+                current.push(index);
+                if current.len() > 1 && !fits(&current) {
+                    current.pop();
+                    parts.push(std::mem::replace(&mut current, vec![index]));
+                }
+            }
+        }
+        if !current.is_empty() || parts.is_empty() {
+            parts.push(current);
+        }
+
+        let mut part_of = HashMap::new();
+        let parts = parts
+            .into_iter()
+            .enumerate()
+            .map(|(part, mut members)| {
+                members.sort_unstable();
+                members
+                    .into_iter()
+                    .map(|index| {
+                        part_of.insert(nodes[index].id.as_str(), part);
+                        nodes[index]
+                    })
+                    .collect()
+            })
+            .collect();
+
+        SplitFlowchart {
+            flowchart: self,
+            parts,
+            part_of,
+        }
+    }
+}
+
+/// Returns the nodes and edges needed to draw the members, including stubs
+/// for their neighbors.
+fn diagram_size(
+    members: &[usize],
+    neighbors: &[Vec<(usize, usize)>],
+) -> (usize, usize) {
+    let members_set: HashSet<usize> = members.iter().copied().collect();
+    let mut stubs = HashSet::new();
+    let mut edges = HashSet::new();
+    for &member in members {
+        for &(neighbor, edge) in &neighbors[member] {
+            edges.insert(edge);
+            if !members_set.contains(&neighbor) {
+                stubs.insert(neighbor);
+            }
+        }
+    }
+    (members.len() + stubs.len(), edges.len())
+}
+
+/// Returns the connected components, in order of their first node.
+fn islands(neighbors: &[Vec<(usize, usize)>]) -> Vec<Vec<usize>> {
+    let all: Vec<usize> = (0..neighbors.len()).collect();
+    let mut islands: Vec<Vec<usize>> = Vec::new();
+    let mut island_of = vec![None; neighbors.len()];
+    for index in breadth_first(&all, neighbors) {
+        let island = neighbors[index]
+            .iter()
+            .find_map(|&(neighbor, _)| island_of[neighbor])
+            .unwrap_or_else(|| {
+                islands.push(Vec::new());
+                islands.len() - 1
+            });
+        island_of[index] = Some(island);
+        islands[island].push(index);
+    }
+    islands
+}
+
+/// Orders the members so that neighbors are near each other, staying within
+/// the members.
+fn breadth_first(
+    members: &[usize],
+    neighbors: &[Vec<(usize, usize)>],
+) -> Vec<usize> {
+    let members_set: HashSet<usize> = members.iter().copied().collect();
+    let mut visited = HashSet::new();
+    let mut order = Vec::with_capacity(members.len());
+    for &start in members {
+        if !visited.insert(start) {
+            continue;
+        }
+        let mut queue = VecDeque::from([start]);
+        while let Some(index) = queue.pop_front() {
+            order.push(index);
+            for &(neighbor, _) in &neighbors[index] {
+                if members_set.contains(&neighbor) && visited.insert(neighbor) {
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+    }
+    order
+}
+
+impl<'a> SplitFlowchart<'a> {
+    pub fn len(&self) -> usize {
+        self.parts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parts.is_empty()
+    }
+
+    pub fn parts(&self) -> impl Iterator<Item = FlowchartPart<'_>> {
+        (0..self.parts.len()).map(|index| FlowchartPart { split: self, index })
+    }
+}
+
+impl std::fmt::Display for FlowchartPart<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let split = self.split;
+        split.flowchart.fmt_diagram(
+            f,
+            &split.parts[self.index],
+            Some(PartInfo {
+                index: self.index,
+                count: split.parts.len(),
+                part_of: &split.part_of,
+            }),
+        )
+    }
+}
+
+impl Flowchart<'_> {
+    fn fmt_node(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        node: &Node,
+    ) -> std::fmt::Result {
+        write!(f, "  {}", node.id)?;
+        if !node.text.is_empty() {
+            let text = mermaid_quote(&node.text);
+            match node.kind {
+                NodeKind::Issue => write!(f, "({text})")?,
+                NodeKind::PullRequest => write!(f, "{{{{{text}}}}}")?,
+            }
+        }
+        writeln!(f)?;
+        match node.state {
+            GithubIssueState::Open => {
+                writeln!(f, "  class {} state-open", node.id)?;
+            }
+            GithubIssueState::Closed => {
+                writeln!(f, "  class {} state-closed", node.id)?;
+            }
+        }
+        for label in &node.labels {
+            if let Some(index) = self.style.labels.get_index_of(label) {
+                writeln!(f, "  class {} label-{index}", node.id)?;
+            }
+        }
+        if let Some(link) = node.link() {
+            writeln!(f, "  click {} {}", node.id, mermaid_quote(link))?;
+        }
+        Ok(())
+    }
+
+    fn fmt_edges(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        node: &Node,
+    ) -> std::fmt::Result {
+        let mut result = Ok(());
+        self.for_each_edge(node, |from, to, kind| {
+            let arrow = match kind {
+                EdgeKind::DependsOn => "-->",
+                EdgeKind::Closes => "-.->",
+            };
+            if result.is_ok() {
+                result = writeln!(f, "  {} {arrow} {}", from.id, to.id);
+            }
+        });
+        result
+    }
+}
+
 /// See <https://mermaid.js.org/syntax/flowchart.html#special-characters-that-break-syntax>
-fn mermaid_quote(text: &str) -> String {
+pub(crate) fn mermaid_quote(text: &str) -> String {
     format!("\"{}\"", text.replace('#', "#35;").replace('\"', "#quot;"))
 }
+
+#[cfg(test)]
+mod tests {
+    use indexmap::indexset;
+
+    use super::*;
+    use crate::clock::FixedClock;
+
+    fn node(
+        id: &str,
+        state: GithubIssueState,
+        depends_on: &[&str],
+        updated_at: OffsetDateTime,
+    ) -> Node {
+        Node {
+            id: id.to_owned(),
+            kind: NodeKind::Issue,
+            text: format!("Issue {id}"),
+            url: format!("https://github.com/foo/bar/issues/{id}"),
+            state,
+            labels: Vec::new(),
+            assignees: Vec::new(),
+            project_titles: indexset! {},
+            fields: IndexMap::new(),
+            milestone: None,
+            depends_on_urls: depends_on
+                .iter()
+                .map(|id| format!("https://github.com/foo/bar/issues/{id}"))
+                .collect(),
+            closes_urls: indexset! {},
+            blocks_count: 0,
+            created_at: None,
+            closed_at: None,
+            updated_at: Some(updated_at),
+        }
+    }
+
+    #[test]
+    fn test_recently_closed() {
+        let closed_at = crate::clock::parse_date("2024-01-10").unwrap();
+        let mut graph = Graph::default();
+        for node in [
+            node("1", GithubIssueState::Open, &["2"], closed_at),
+            node("2", GithubIssueState::Closed, &[], closed_at),
+        ] {
+            graph.nodes.insert(node.url.clone(), node);
+        }
+        graph.nodes[1].blocks_count = 1;
+
+        let render = |as_of: &str| {
+            let filter = Filter {
+                closed_within: Some(Duration::days(7)),
+                clock: Box::new(FixedClock(
+                    crate::clock::parse_date(as_of).unwrap(),
+                )),
+                ..Filter::default()
+            };
+            Flowchart::new(&graph, String::new(), false, filter).to_string()
+        };
+
+        // Closed within the last 7 days.
+        let output = render("2024-01-15");
+        assert!(output.contains("  2(\"Issue 2\")\n"));
+        assert!(output.contains("  2 --> 1\n"));
+
+        // Closed too long ago.
+        let output = render("2024-02-15");
+        assert!(!output.contains("  2(\"Issue 2\")\n"));
+        assert!(!output.contains("  2 --> 1\n"));
+        assert!(output.contains("  1(\"Issue 1\")\n"));
+    }
+
+    #[test]
+    fn test_split() {
+        let now = crate::clock::parse_date("2024-01-10").unwrap();
+        let open = GithubIssueState::Open;
+        let mut graph = Graph::default();
+        // Islands 1-2-3 and 4-5, and 6 on its own.
+        for node in [
+            node("1", open, &[], now),
+            node("2", open, &["1"], now),
+            node("3", open, &["2"], now),
+            node("4", open, &[], now),
+            node("5", open, &["4"], now),
+            node("6", open, &[], now),
+        ] {
+            graph.nodes.insert(node.url.clone(), node);
+        }
+        let parts = |limits| {
+            let mut flowchart =
+                Flowchart::new(&graph, String::new(), true, Filter::default());
+            flowchart.limits = limits;
+            let split = flowchart.split();
+            let ids: Vec<Vec<String>> = split
+                .parts
+                .iter()
+                .map(|part| part.iter().map(|node| node.id.clone()).collect())
+                .collect();
+            let output: Vec<String> =
+                split.parts().map(|part| part.to_string()).collect();
+            (ids, output)
+        };
+
+        let (ids, output) = parts(Limits::default());
+        assert_eq!(ids, [["1", "2", "3", "4", "5", "6"]]);
+        assert!(!output[0].contains("stub"));
+
+        // Islands are packed together when they fit.
+        let (ids, _) = parts(Limits {
+            max_nodes: Some(3),
+            max_edges: None,
+        });
+        assert_eq!(ids, [vec!["1", "2", "3"], vec!["4", "5", "6"]]);
+
+        // An island that's too big is split, with stubs for the other parts.
+        let (ids, output) = parts(Limits {
+            max_nodes: None,
+            max_edges: Some(1),
+        });
+        assert_eq!(ids, [vec!["1"], vec!["2"], vec!["3"], vec!["4", "5", "6"]]);
+        assert!(output[0].contains("  1 --> 2\n"));
+        assert!(output[0].contains("  2[[\"Issue 2 (diagram 2)\"]]\n"));
+        assert!(output[1].contains("  1[[\"Issue 1 (diagram 1)\"]]\n"));
+        assert!(output[1].contains("  3[[\"Issue 3 (diagram 3)\"]]\n"));
+        assert!(!output[3].contains("[["));
+    }
+
+    #[test]
+    fn test_reduce() {
+        let now = crate::clock::parse_date("2024-01-10").unwrap();
+        let open = GithubIssueState::Open;
+        let mut graph = Graph::default();
+        for node in [
+            node("1", open, &["2", "3"], now),
+            node("2", open, &["3"], now),
+            node("3", open, &[], now),
+            // A cycle with a shortcut isn't reduced.
+            node("4", open, &["5", "6"], now),
+            node("5", open, &["6"], now),
+            node("6", open, &["5"], now),
+        ] {
+            graph.nodes.insert(node.url.clone(), node);
+        }
+        let mut flowchart =
+            Flowchart::new(&graph, String::new(), true, Filter::default());
+        flowchart.reduce = true;
+
+        let output = flowchart.to_string();
+        assert!(output.contains("  2 --> 1\n"));
+        assert!(output.contains("  3 --> 2\n"));
+        assert!(!output.contains("  3 --> 1\n"));
+        assert!(output.contains("  6 --> 4\n"));
+
+        let data = flowchart.data();
+        let redundant: Vec<(&str, &str)> = data
+            .edges
+            .iter()
+            .filter(|edge| edge.redundant)
+            .map(|edge| (edge.from.as_str(), edge.to.as_str()))
+            .collect();
+        assert_eq!(redundant, [("3", "1")]);
+    }
+
+    #[test]
+    fn test_gantt() {
+        let date = |date| crate::clock::parse_date(date).unwrap();
+        let now = date("2024-01-10");
+        let open = GithubIssueState::Open;
+        let mut graph = Graph::default();
+        let mut closed = node("3", GithubIssueState::Closed, &[], now);
+        closed.created_at = Some(date("2024-01-02"));
+        closed.closed_at = Some(date("2024-01-05"));
+        let mut sized = node("2", open, &["3"], now);
+        sized.labels = vec!["size: 2".to_owned()];
+        sized.milestone = Some("v1".to_owned());
+        for node in [node("1", open, &["2", "3"], now), sized, closed] {
+            graph.nodes.insert(node.url.clone(), node);
+        }
+        let filter = Filter {
+            clock: Box::new(FixedClock(now)),
+            ..Filter::default()
+        };
+        let flowchart = Flowchart::new(&graph, String::new(), true, filter);
+
+        let output = flowchart.gantt().unwrap().to_string();
+        assert!(output.contains(
+            "\
+  section foo/bar
+  Issue 1 :t1, after t2, 1d
+  Issue 3 :done, t3, 2024-01-02 00:00, 2024-01-05 00:00
+  section v1
+  Issue 2 :t2, 2024-01-10 00:00, 2d
+"
+        ));
+
+        // A cycle among issues that aren't drawn doesn't matter.
+        for node in graph.nodes.values_mut() {
+            node.project_titles.insert("Roadmap".to_owned());
+        }
+        for node in [node("4", open, &["5"], now), node("5", open, &["4"], now)]
+        {
+            graph.nodes.insert(node.url.clone(), node);
+        }
+        let filter = |include_project_only: Option<&str>| Filter {
+            include_project_only: include_project_only.map(str::to_owned),
+            clock: Box::new(FixedClock(now)),
+            ..Filter::default()
+        };
+        let flowchart =
+            Flowchart::new(&graph, String::new(), false, filter(None));
+        assert!(matches!(flowchart.gantt(), Err(GraphError::Cycles { .. })));
+        let flowchart = Flowchart::new(
+            &graph,
+            String::new(),
+            false,
+            filter(Some("Roadmap")),
+        );
+        let output = flowchart.gantt().unwrap().to_string();
+        assert!(output.contains("Issue 1 :t1, after t2, 1d"));
+        assert!(!output.contains("Issue 4"));
+    }
+}
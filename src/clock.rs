@@ -0,0 +1,76 @@
+use time::format_description::well_known::Rfc3339;
+use time::macros::format_description;
+use time::OffsetDateTime;
+
+use crate::error::{Error, Result};
+
+/// The source of the current time.  Use [`FixedClock`] for reproducible
+/// output.
+pub trait Clock: std::fmt::Debug + Send + Sync {
+    fn now(&self) -> OffsetDateTime;
+}
+
+/// The system's clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> OffsetDateTime {
+        OffsetDateTime::now_utc()
+    }
+}
+
+/// A clock that's stopped at a given time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FixedClock(pub OffsetDateTime);
+
+impl Clock for FixedClock {
+    fn now(&self) -> OffsetDateTime {
+        self.0
+    }
+}
+
+/// Parses an RFC 3339 date time, e.g. "2024-01-31T12:00:00Z", or a date, e.g.
+/// "2024-01-31", which means midnight UTC.
+pub fn parse_date(value: &str) -> Result<OffsetDateTime> {
+    let date_time_error = match OffsetDateTime::parse(value, &Rfc3339) {
+        Ok(date_time) => return Ok(date_time),
+        Err(error) => error,
+    };
+
+    match parse_calendar_date(value) {
+        Ok(date) => Ok(date.midnight().assume_utc()),
+        // Report the error for the full format.
+        Err(_) => Err(Error::Date {
+            value: value.to_owned(),
+            source: date_time_error,
+        }),
+    }
+}
+
+/// Parses a date, e.g. "2024-01-31".
+pub(crate) fn parse_calendar_date(
+    value: &str,
+) -> std::result::Result<time::Date, time::error::Parse> {
+    time::Date::parse(value, format_description!("[year]-[month]-[day]"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_date() {
+        let expected =
+            time::Date::from_calendar_date(2024, time::Month::January, 31)
+                .unwrap()
+                .midnight()
+                .assume_utc();
+
+        assert_eq!(parse_date("2024-01-31T00:00:00Z").unwrap(), expected);
+        assert_eq!(parse_date("2024-01-31").unwrap(), expected);
+        assert_eq!(parse_date("2024-01-31T09:00:00+09:00").unwrap(), expected);
+        assert!(matches!(parse_date("yesterday"), Err(Error::Date { .. })));
+        assert!(parse_date("2024-01-32").is_err());
+    }
+}
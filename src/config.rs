@@ -0,0 +1,202 @@
+use std::path::{Path, PathBuf};
+
+use crate::chart::{GroupBy, Style};
+
+/// The file name that's used when no config file is given.
+pub const DEFAULT_CONFIG_FILE_NAME: &str = "techtree.toml";
+
+/// Project-level configuration, typically checked in as `techtree.toml`.
+/// Command line arguments override these values.
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// JSON Issues List files, directories, or glob patterns.  Relative
+    /// paths are relative to the config file.
+    pub issues: Vec<PathBuf>,
+    /// JSON Pull Requests List files.  Relative paths are relative to the
+    /// config file.
+    pub prs: Vec<PathBuf>,
+    /// JSON files from `gh project item-list`.  Relative paths are relative
+    /// to the config file.
+    pub project_items: Vec<PathBuf>,
+    /// The title of the project that `project_items` are from.
+    pub project_title: Option<String>,
+    /// YAML or TOML files of tasks that aren't GitHub issues.  Relative
+    /// paths are relative to the config file.
+    pub tasks: Vec<PathBuf>,
+    /// CSV files exported from Jira.  Relative paths are relative to the
+    /// config file.
+    pub jira_csv: Vec<PathBuf>,
+    pub header: Option<String>,
+    pub title: Option<String>,
+    pub all: bool,
+    pub include_project: Option<String>,
+    pub prior_days: Option<u16>,
+    pub github_hosts: Vec<String>,
+    /// The base URL of a Jira site, e.g. "https://example.atlassian.net".
+    pub jira_url: Option<String>,
+    pub group_by: Option<GroupBy>,
+    /// Don't draw dependencies that are implied by others.
+    pub reduce: bool,
+    /// Split Mermaid output into diagrams with at most this many nodes.
+    pub max_nodes: Option<usize>,
+    /// Split Mermaid output into diagrams with at most this many edges.
+    pub max_edges: Option<usize>,
+    pub style: Style,
+    pub keywords: KeywordsConfig,
+}
+
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeywordsConfig {
+    /// Line prefixes that declare a dependency, e.g. "Depends on".  Matching
+    /// is case-insensitive.  Defaults to "Depends on".  Keywords can't be
+    /// blank, since they'd match every line.
+    pub depends_on: Vec<String>,
+}
+
+impl Config {
+    /// Loads the config file at the given path, or `techtree.toml` in the
+    /// current directory if it exists.
+    pub fn discover(path: Option<&Path>) -> Result<Self, ConfigError> {
+        match path {
+            Some(path) => Self::load(path),
+            None => {
+                let path = Path::new(DEFAULT_CONFIG_FILE_NAME);
+                if path.is_file() {
+                    Self::load(path)
+                } else {
+                    Ok(Self::default())
+                }
+            }
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let text =
+            std::fs::read_to_string(path).map_err(|error| ConfigError {
+                path: path.to_owned(),
+                message: error.to_string(),
+            })?;
+        let mut config: Config =
+            toml::from_str(&text).map_err(|error| ConfigError {
+                path: path.to_owned(),
+                message: error.to_string(),
+            })?;
+
+        if config
+            .keywords
+            .depends_on
+            .iter()
+            .any(|keyword| keyword.trim().is_empty())
+        {
+            return Err(ConfigError {
+                path: path.to_owned(),
+                message: "keywords.depends_on has a blank keyword".to_owned(),
+            });
+        }
+
+        // Make input paths relative to the config file.
+        let base = path.parent().unwrap_or(Path::new(""));
+        for input in config
+            .issues
+            .iter_mut()
+            .chain(config.prs.iter_mut())
+            .chain(config.project_items.iter_mut())
+            .chain(config.tasks.iter_mut())
+            .chain(config.jira_csv.iter_mut())
+        {
+            if input.is_relative() && input != Path::new("-") {
+                *input = base.join(&*input);
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+#[derive(Debug)]
+pub struct ConfigError {
+    path: PathBuf,
+    message: String,
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Failed to load config file {}: {}",
+            self.path.display(),
+            self.message
+        )
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dir(name: &str) -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("techtree-test-{name}-{}", std::process::id()))
+    }
+
+    /// Writes the config to a file in a new directory and loads it.
+    fn load(name: &str, toml: &str) -> Result<Config, ConfigError> {
+        let dir = test_dir(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(DEFAULT_CONFIG_FILE_NAME);
+        std::fs::write(&path, toml).unwrap();
+        let config = Config::discover(Some(&path));
+        std::fs::remove_dir_all(&dir).unwrap();
+        config
+    }
+
+    #[test]
+    fn test_load() {
+        let config = load(
+            "load",
+            r#"
+            issues = ["local/issues.json", "/tmp/issues.json", "-"]
+            tasks = ["tasks.yaml"]
+            all = true
+            prior_days = 3
+
+            [keywords]
+            depends_on = ["Needs"]
+            "#,
+        )
+        .unwrap();
+        let dir = test_dir("load");
+
+        // Relative to the config file.
+        assert_eq!(
+            config.issues,
+            [
+                dir.join("local/issues.json"),
+                PathBuf::from("/tmp/issues.json"),
+                PathBuf::from("-"),
+            ]
+        );
+        assert_eq!(config.tasks, [dir.join("tasks.yaml")]);
+        assert!(config.all);
+        assert!(!config.reduce);
+        assert_eq!(config.prior_days, Some(3));
+        assert_eq!(config.keywords.depends_on, ["Needs"]);
+    }
+
+    #[test]
+    fn test_load_errors() {
+        let error = load("unknown", "colour = true").unwrap_err();
+        assert!(error.to_string().contains("colour"), "{error}");
+
+        let error = load("blank", "keywords.depends_on = [\"Needs\", \" \"]")
+            .unwrap_err();
+        assert!(error.to_string().contains("blank keyword"), "{error}");
+
+        let missing = Path::new("/nonexistent/techtree.toml");
+        assert!(Config::discover(Some(missing)).is_err());
+    }
+}
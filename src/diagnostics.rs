@@ -0,0 +1,143 @@
+/// How serious a diagnostic is.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// Identifies the kind of problem so that tools can filter and count them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum Code {
+    /// A dependency line doesn't contain a recognizable issue reference.
+    MalformedReference,
+    /// An item's URL doesn't contain a repository.
+    UnknownRepository,
+    /// An item in an input file couldn't be parsed and was skipped.
+    InvalidItem,
+    /// An item is missing a date that's needed, e.g. when it was closed.
+    MissingDate,
+    /// An input refers to an item that isn't in the inputs.
+    UnknownItem,
+    /// Inputs have different copies of the same item, e.g. from overlapping
+    /// exports.
+    ConflictingItem,
+}
+
+impl Code {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Code::MalformedReference => "malformed-reference",
+            Code::UnknownRepository => "unknown-repository",
+            Code::InvalidItem => "invalid-item",
+            Code::MissingDate => "missing-date",
+            Code::UnknownItem => "unknown-item",
+            Code::ConflictingItem => "conflicting-item",
+        }
+    }
+}
+
+impl std::fmt::Display for Code {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct Diagnostic {
+    pub code: Code,
+    pub severity: Severity,
+    pub message: String,
+    /// The URL of the item the problem was found in, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub item: Option<String>,
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}[{}]: {}", self.severity, self.code, self.message)?;
+        if let Some(item) = &self.item {
+            write!(f, " in {item}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Collects problems found while loading and analyzing items, so that callers
+/// decide how to report them.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+#[serde(transparent)]
+pub struct Diagnostics {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.push(diagnostic);
+    }
+
+    pub fn warn(
+        &mut self,
+        code: Code,
+        message: impl Into<String>,
+        item: Option<&str>,
+    ) {
+        self.push(Diagnostic {
+            code,
+            severity: Severity::Warning,
+            message: message.into(),
+            item: item.map(str::to_owned),
+        });
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Diagnostic> {
+        self.diagnostics.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.diagnostics.len()
+    }
+}
+
+impl<'a> IntoIterator for &'a Diagnostics {
+    type Item = &'a Diagnostic;
+    type IntoIter = std::slice::Iter<'a, Diagnostic>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl Extend<Diagnostic> for Diagnostics {
+    fn extend<T: IntoIterator<Item = Diagnostic>>(&mut self, iter: T) {
+        self.diagnostics.extend(iter);
+    }
+}
+
+impl IntoIterator for Diagnostics {
+    type Item = Diagnostic;
+    type IntoIter = std::vec::IntoIter<Diagnostic>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.diagnostics.into_iter()
+    }
+}
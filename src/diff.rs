@@ -0,0 +1,312 @@
+use indexmap::{IndexMap, IndexSet};
+
+use crate::chart::mermaid_quote;
+use crate::graph::{Graph, Node};
+use crate::url;
+use crate::util::markdown_link;
+
+/// A dependency, where the prerequisite blocks the dependent.
+#[derive(Debug, Clone, Copy)]
+pub struct Edge<'a> {
+    pub prerequisite: &'a Node,
+    pub dependent: &'a Node,
+}
+
+/// The changes between two snapshots of the same items, e.g. last week's
+/// export and today's.
+#[derive(Debug, Default)]
+pub struct GraphDiff<'a> {
+    /// Items only in the newer snapshot.
+    pub added: Vec<&'a Node>,
+    /// Items only in the older snapshot.
+    pub removed: Vec<&'a Node>,
+    /// Dependencies only in the newer snapshot.
+    pub added_edges: Vec<Edge<'a>>,
+    /// Dependencies only in the older snapshot.
+    pub removed_edges: Vec<Edge<'a>>,
+    /// Items that were open and are now closed.
+    pub closed: Vec<&'a Node>,
+    /// Open items that were blocked by an open item and now aren't.
+    pub unblocked: Vec<&'a Node>,
+}
+
+impl<'a> GraphDiff<'a> {
+    /// Compares an older snapshot to a newer one.  Nodes in the result are
+    /// from the newer snapshot, except for removed items and dependencies.
+    pub fn new(before: &'a Graph, after: &'a Graph) -> Self {
+        let mut diff = GraphDiff::default();
+
+        for (url, node) in &after.nodes {
+            let Some(old_node) = before.nodes.get(url) else {
+                diff.added.push(node);
+                continue;
+            };
+            if old_node.is_open() && !node.is_open() {
+                diff.closed.push(node);
+            }
+            if node.is_open()
+                && before.is_blocked(old_node)
+                && !after.is_blocked(node)
+            {
+                diff.unblocked.push(node);
+            }
+        }
+        diff.removed = before
+            .nodes
+            .iter()
+            .filter(|(url, _)| !after.nodes.contains_key(*url))
+            .map(|(_, node)| node)
+            .collect();
+
+        let before_edges = edges(before);
+        let after_edges = edges(after);
+        diff.added_edges = after_edges
+            .iter()
+            .filter(|(key, _)| !before_edges.contains_key(*key))
+            .map(|(_, edge)| *edge)
+            .collect();
+        diff.removed_edges = before_edges
+            .iter()
+            .filter(|(key, _)| !after_edges.contains_key(*key))
+            .map(|(_, edge)| *edge)
+            .collect();
+
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.added_edges.is_empty()
+            && self.removed_edges.is_empty()
+            && self.closed.is_empty()
+            && self.unblocked.is_empty()
+    }
+}
+
+/// Returns the dependencies between nodes in the graph, keyed by the
+/// prerequisite's and dependent's URLs.
+fn edges(graph: &Graph) -> IndexMap<(&str, &str), Edge<'_>> {
+    let mut edges = IndexMap::new();
+    for (url, dependent) in &graph.nodes {
+        for depends_on_url in &dependent.depends_on_urls {
+            if let Some((_, prerequisite_url, prerequisite)) =
+                graph.nodes.get_full(depends_on_url)
+            {
+                edges.insert(
+                    (prerequisite_url.as_str(), url.as_str()),
+                    Edge {
+                        prerequisite,
+                        dependent,
+                    },
+                );
+            }
+        }
+    }
+    edges
+}
+
+/// Formats the changes as a Markdown report.
+impl std::fmt::Display for GraphDiff<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No changes.");
+        }
+
+        let node_sections = [
+            ("Closed", &self.closed),
+            ("Unblocked", &self.unblocked),
+            ("Added", &self.added),
+            ("Removed", &self.removed),
+        ];
+        for (heading, nodes) in node_sections {
+            if nodes.is_empty() {
+                continue;
+            }
+            writeln!(f, "### {heading} ({})", nodes.len())?;
+            writeln!(f)?;
+            for node in nodes {
+                writeln!(f, "- {}", markdown_link(node))?;
+            }
+            writeln!(f)?;
+        }
+
+        let edge_sections = [
+            ("Added Dependencies", &self.added_edges),
+            ("Removed Dependencies", &self.removed_edges),
+        ];
+        for (heading, edges) in edge_sections {
+            if edges.is_empty() {
+                continue;
+            }
+            writeln!(f, "### {heading} ({})", edges.len())?;
+            writeln!(f)?;
+            for edge in edges {
+                writeln!(
+                    f,
+                    "- {} blocks {}",
+                    markdown_link(edge.prerequisite),
+                    markdown_link(edge.dependent)
+                )?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Renders the changes as a Mermaid flowchart.  Only changed items and their
+/// neighbors are drawn.
+#[derive(Debug)]
+pub struct DiffFlowchart<'a> {
+    diff: &'a GraphDiff<'a>,
+    after: &'a Graph,
+}
+
+impl<'a> DiffFlowchart<'a> {
+    /// The graph should be the newer snapshot that the diff was created with.
+    pub fn new(diff: &'a GraphDiff<'a>, after: &'a Graph) -> Self {
+        Self { diff, after }
+    }
+}
+
+impl std::fmt::Display for DiffFlowchart<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Nodes from both snapshots, keyed by canonical URL, with the class
+        // of change.  IDs can't be reused since they differ between
+        // snapshots.
+        let mut nodes: IndexMap<String, (&Node, Option<&str>)> =
+            IndexMap::new();
+        let changes = [
+            ("added", &self.diff.added),
+            ("removed", &self.diff.removed),
+            ("closed", &self.diff.closed),
+            ("unblocked", &self.diff.unblocked),
+        ];
+        for (class, changed) in changes {
+            for node in changed {
+                nodes.insert(url::canonical(&node.url), (node, Some(class)));
+            }
+        }
+        let changed_edges =
+            self.diff.added_edges.iter().chain(&self.diff.removed_edges);
+        for edge in changed_edges {
+            for node in [edge.prerequisite, edge.dependent] {
+                nodes
+                    .entry(url::canonical(&node.url))
+                    .or_insert((node, None));
+            }
+        }
+
+        writeln!(f, "flowchart LR")?;
+        writeln!(f, "  classDef added stroke:#317236,stroke-width:8px")?;
+        writeln!(
+            f,
+            "  classDef removed stroke:#CF222E,stroke-width:4px,stroke-dasharray:5 5,color:#636871"
+        )?;
+        writeln!(f, "  classDef closed stroke:#7048D4,stroke-width:8px")?;
+        writeln!(f, "  classDef unblocked stroke:#BF8700,stroke-width:8px")?;
+
+        for (index, (node, class)) in nodes.values().enumerate() {
+            writeln!(f, "  n{index}({})", mermaid_quote(&node.text))?;
+            if let Some(class) = class {
+                writeln!(f, "  class n{index} {class}")?;
+            }
+            if let Some(link) = node.link() {
+                writeln!(f, "  click n{index} {}", mermaid_quote(link))?;
+            }
+        }
+
+        let id = |node: &Node| {
+            nodes
+                .get_index_of(&url::canonical(&node.url))
+                .map(|index| format!("n{index}"))
+        };
+        let mut drawn = IndexSet::new();
+        for (edge, arrow) in self
+            .diff
+            .added_edges
+            .iter()
+            .map(|edge| (edge, "==>"))
+            .chain(self.diff.removed_edges.iter().map(|edge| (edge, "-.-x")))
+        {
+            if let (Some(from), Some(to)) =
+                (id(edge.prerequisite), id(edge.dependent))
+            {
+                writeln!(f, "  {from} {arrow} {to}")?;
+                drawn.insert((from, to));
+            }
+        }
+        // Unchanged dependencies between the drawn nodes, for context.
+        for (node, _) in nodes.values() {
+            let Some(dependent) = self.after.get(&node.url) else {
+                continue;
+            };
+            for prerequisite in self.after.dependencies(dependent) {
+                if let (Some(from), Some(to)) = (id(prerequisite), id(node)) {
+                    if !drawn.contains(&(from.clone(), to.clone())) {
+                        writeln!(f, "  {from} --> {to}")?;
+                        drawn.insert((from, to));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::test_util::{graph, issue};
+
+    #[test]
+    fn test_diff() {
+        let before = graph([
+            issue(1, "OPEN", "Depends on: #2\nDepends on: #4"),
+            issue(2, "OPEN", ""),
+            issue(3, "OPEN", ""),
+            issue(4, "CLOSED", ""),
+        ]);
+        let after = graph([
+            issue(1, "OPEN", "Depends on: #2\nDepends on: #5"),
+            issue(2, "CLOSED", ""),
+            issue(4, "CLOSED", ""),
+            issue(5, "CLOSED", ""),
+        ]);
+        let diff = GraphDiff::new(&before, &after);
+        let titles = |nodes: &[&Node]| {
+            nodes
+                .iter()
+                .map(|node| node.text.clone())
+                .collect::<Vec<_>>()
+        };
+        let edge_titles = |edges: &[Edge<'_>]| {
+            edges
+                .iter()
+                .map(|edge| {
+                    (
+                        edge.prerequisite.text.clone(),
+                        edge.dependent.text.clone(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(titles(&diff.added), ["Issue 5"]);
+        assert_eq!(titles(&diff.removed), ["Issue 3"]);
+        assert_eq!(titles(&diff.closed), ["Issue 2"]);
+        assert_eq!(titles(&diff.unblocked), ["Issue 1"]);
+        assert_eq!(
+            edge_titles(&diff.added_edges),
+            [("Issue 5".to_owned(), "Issue 1".to_owned())]
+        );
+        assert_eq!(
+            edge_titles(&diff.removed_edges),
+            [("Issue 4".to_owned(), "Issue 1".to_owned())]
+        );
+        assert!(GraphDiff::new(&after, &after).is_empty());
+    }
+}
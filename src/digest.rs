@@ -0,0 +1,232 @@
+use indexmap::IndexMap;
+use time::OffsetDateTime;
+
+use crate::graph::{Graph, Node, NodeKind};
+use crate::util::markdown_link;
+
+/// An open issue whose last open prerequisite closed recently.
+#[derive(Debug, Clone, Copy)]
+pub struct Unblocked<'g> {
+    pub node: &'g Node,
+    /// The prerequisite that closed last.
+    pub prerequisite: &'g Node,
+    pub closed_at: OffsetDateTime,
+}
+
+/// Issues that became unblocked within a time window, grouped by assignee and
+/// then by project.  `None` keys are for unassigned issues and issues not in
+/// a project.
+#[derive(Debug)]
+pub struct Digest<'g> {
+    pub since: OffsetDateTime,
+    pub until: OffsetDateTime,
+    pub groups:
+        IndexMap<Option<String>, IndexMap<Option<String>, Vec<Unblocked<'g>>>>,
+}
+
+impl<'g> Digest<'g> {
+    /// Finds open issues whose prerequisites are all closed, where the last
+    /// one closed after `since` and no later than `until`.  Issues with a
+    /// prerequisite that's missing a close date are left out.
+    pub fn new(
+        graph: &'g Graph,
+        since: OffsetDateTime,
+        until: OffsetDateTime,
+    ) -> Self {
+        let mut groups: IndexMap<_, IndexMap<_, Vec<_>>> = IndexMap::new();
+
+        for node in graph.nodes.values() {
+            if node.kind != NodeKind::Issue || !node.is_open() {
+                continue;
+            }
+            let Some(unblocked) = last_prerequisite(graph, node) else {
+                continue;
+            };
+            if unblocked.closed_at <= since || unblocked.closed_at > until {
+                continue;
+            }
+
+            let assignees = if node.assignees.is_empty() {
+                vec![None]
+            } else {
+                node.assignees.iter().cloned().map(Some).collect()
+            };
+            let projects = if node.project_titles.is_empty() {
+                vec![None]
+            } else {
+                node.project_titles.iter().cloned().map(Some).collect()
+            };
+            for assignee in assignees {
+                let projects_by_assignee = groups.entry(assignee).or_default();
+                for project in &projects {
+                    projects_by_assignee
+                        .entry(project.clone())
+                        .or_default()
+                        .push(unblocked);
+                }
+            }
+        }
+
+        // Sort by name, with the unassigned and projectless groups last.
+        let order = |a: &Option<String>, b: &Option<String>| {
+            (a.is_none(), a).cmp(&(b.is_none(), b))
+        };
+        groups.sort_by(|a, _, b, _| order(a, b));
+        for projects in groups.values_mut() {
+            projects.sort_by(|a, _, b, _| order(a, b));
+        }
+
+        Self {
+            since,
+            until,
+            groups,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+}
+
+/// Returns the prerequisite that closed last, if the node has prerequisites
+/// and they're all closed with known dates.
+fn last_prerequisite<'g>(
+    graph: &'g Graph,
+    node: &'g Node,
+) -> Option<Unblocked<'g>> {
+    let mut last: Option<Unblocked<'g>> = None;
+    for prerequisite in graph.dependencies(node) {
+        if prerequisite.is_open() {
+            return None;
+        }
+        let closed_at = prerequisite.closed_at?;
+        if last.map_or(true, |last| closed_at > last.closed_at) {
+            last = Some(Unblocked {
+                node,
+                prerequisite,
+                closed_at,
+            });
+        }
+    }
+    last
+}
+
+/// Formats the digest as Markdown, e.g. for an issue comment.
+impl std::fmt::Display for Digest<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "## Newly Unblocked Work")?;
+        writeln!(f)?;
+        if self.is_empty() {
+            return writeln!(
+                f,
+                "No issues were unblocked between {} and {}.",
+                self.since.date(),
+                self.until.date()
+            );
+        }
+        writeln!(
+            f,
+            "Issues whose last open prerequisite closed between {} and {}.",
+            self.since.date(),
+            self.until.date()
+        )?;
+
+        for (assignee, projects) in &self.groups {
+            writeln!(f)?;
+            match assignee {
+                // Not "@login", which would notify everyone in the digest.
+                Some(login) => writeln!(f, "### `{login}`")?,
+                None => writeln!(f, "### Unassigned")?,
+            }
+            for (project, items) in projects {
+                writeln!(f)?;
+                match project {
+                    Some(title) => writeln!(f, "**{title}**")?,
+                    None => writeln!(f, "**No project**")?,
+                }
+                writeln!(f)?;
+                for item in items {
+                    writeln!(
+                        f,
+                        "- {}, after {} closed on {}",
+                        markdown_link(item.node),
+                        markdown_link(item.prerequisite),
+                        item.closed_at.date()
+                    )?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::clock::parse_date;
+    use crate::graph::test_util::{graph, issue, with};
+
+    #[test]
+    fn test_digest() {
+        let closed = |number, date: &str| {
+            with(
+                issue(number, "CLOSED", ""),
+                json!({"closedAt": format!("{date}T00:00:00Z")}),
+            )
+        };
+        let graph = graph([
+            closed(1, "2024-01-03"),
+            closed(2, "2024-01-05"),
+            closed(3, "2023-12-01"),
+            issue(4, "OPEN", ""),
+            // Unblocked by #2.
+            with(
+                issue(10, "OPEN", "Depends on: #1\nDepends on: #2"),
+                json!({"assignees": [{"id": "a", "login": "alice", "name": ""}]}),
+            ),
+            // Unblocked before the window.
+            issue(11, "OPEN", "Depends on: #3"),
+            // Still blocked.
+            issue(12, "OPEN", "Depends on: #2\nDepends on: #4"),
+            // Unassigned.
+            issue(13, "OPEN", "Depends on: #1"),
+        ]);
+
+        let digest = Digest::new(
+            &graph,
+            parse_date("2024-01-01").unwrap(),
+            parse_date("2024-01-08").unwrap(),
+        );
+        let groups: Vec<_> = digest
+            .groups
+            .iter()
+            .map(|(assignee, projects)| {
+                let items: Vec<_> = projects[&None]
+                    .iter()
+                    .map(|item| {
+                        (
+                            item.node.text.as_str(),
+                            item.prerequisite.text.as_str(),
+                        )
+                    })
+                    .collect();
+                (assignee.as_deref(), items)
+            })
+            .collect();
+        assert_eq!(
+            groups,
+            [
+                (Some("alice"), vec![("Issue 10", "Issue 2")]),
+                (None, vec![("Issue 13", "Issue 1")]),
+            ]
+        );
+
+        // Posting the digest doesn't mention assignees.
+        let markdown = digest.to_string();
+        assert!(markdown.contains("\n### `alice`\n"), "{markdown}");
+        assert!(!markdown.contains('@'), "{markdown}");
+    }
+}
@@ -0,0 +1,259 @@
+use std::path::{Path, PathBuf};
+
+use crate::config::ConfigError;
+use crate::parse::KeywordError;
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// An input file couldn't be read.
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// An input path is a glob pattern that's invalid.
+    Pattern {
+        pattern: String,
+        source: glob::PatternError,
+    },
+    /// An input path is a glob pattern or directory that has no files.
+    NoFiles {
+        path: PathBuf,
+    },
+    /// An input file isn't valid JSON or isn't a list.
+    Json {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    /// An item in an input file doesn't match the expected schema.
+    Record {
+        path: PathBuf,
+        /// The index of the item in the input's list.
+        index: usize,
+        /// The item's URL, if it has one.
+        url: Option<String>,
+        source: serde_json::Error,
+    },
+    /// An input file isn't valid TOML or doesn't match the expected schema.
+    Toml {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    /// An input file isn't valid YAML or doesn't match the expected schema.
+    Yaml {
+        path: PathBuf,
+        source: serde_yaml_ng::Error,
+    },
+    /// An input file isn't valid CSV.
+    Csv {
+        path: PathBuf,
+        source: csv::Error,
+    },
+    /// An input CSV file doesn't have a column that's needed.
+    MissingColumn {
+        path: PathBuf,
+        column: String,
+    },
+    /// A date given as an option couldn't be parsed.
+    Date {
+        value: String,
+        source: time::error::Parse,
+    },
+    /// An estimate is negative, isn't finite, or is more than
+    /// [`crate::forecast::MAX_ESTIMATE_DAYS`].
+    Estimate {
+        /// The item the estimate is for, or `None` for the default.
+        item: Option<String>,
+        days: f64,
+    },
+    /// A forecast ends after the latest date that can be represented.
+    ForecastOverflow,
+    Config(ConfigError),
+    /// A keyword that declares a dependency is blank or couldn't be compiled.
+    Keyword(KeywordError),
+    Graph(GraphError),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum GraphError {
+    /// The dependencies form cycles.  In each cycle, each URL depends on the
+    /// next, and the last depends on the first.
+    Cycles { cycles: Vec<Vec<String>> },
+    /// No item matches a reference given by the user.
+    NotFound { reference: String },
+    /// More than one item matches a reference given by the user.
+    Ambiguous {
+        reference: String,
+        urls: Vec<String>,
+    },
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io { path, source } => {
+                write!(f, "Failed to read {}: {source}", display_path(path))
+            }
+            Error::Pattern { pattern, source } => {
+                write!(f, "Invalid glob pattern {pattern:?}: {source}")
+            }
+            Error::NoFiles { path } => {
+                write!(f, "No input files match {}", path.display())
+            }
+            Error::Json { path, source } => {
+                write!(f, "Failed to parse {}: {source}", display_path(path))
+            }
+            Error::Record {
+                path,
+                index,
+                url,
+                source,
+            } => {
+                write!(f, "Failed to parse item {index}")?;
+                if let Some(url) = url {
+                    write!(f, " ({url})")?;
+                }
+                write!(f, " in {}: {source}", display_path(path))
+            }
+            Error::Toml { path, source } => {
+                write!(f, "Failed to parse {}: {source}", display_path(path))
+            }
+            Error::Yaml { path, source } => {
+                write!(f, "Failed to parse {}: {source}", display_path(path))
+            }
+            Error::Csv { path, source } => {
+                write!(f, "Failed to parse {}: {source}", display_path(path))
+            }
+            Error::MissingColumn { path, column } => {
+                write!(f, "{} has no {column:?} column", display_path(path))
+            }
+            Error::Date { value, source } => {
+                write!(f, "Invalid RFC 3339 date time {value:?}: {source}")
+            }
+            Error::Estimate { item, days } => {
+                match item {
+                    Some(item) => write!(f, "Invalid estimate for {item}")?,
+                    None => write!(f, "Invalid default estimate")?,
+                }
+                write!(
+                    f,
+                    " of {days} days; estimates must be from 0 to {} days",
+                    crate::forecast::MAX_ESTIMATE_DAYS
+                )
+            }
+            Error::ForecastOverflow => write!(
+                f,
+                "The forecast ends after the latest date that can be represented"
+            ),
+            Error::Config(error) => error.fmt(f),
+            Error::Keyword(error) => {
+                write!(f, "Invalid dependency keyword: {error}")
+            }
+            Error::Graph(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            Error::Pattern { source, .. } => Some(source),
+            Error::NoFiles { .. } => None,
+            Error::Json { source, .. } | Error::Record { source, .. } => {
+                Some(source)
+            }
+            Error::Toml { source, .. } => Some(source),
+            Error::Yaml { source, .. } => Some(source),
+            Error::Csv { source, .. } => Some(source),
+            Error::MissingColumn { .. } => None,
+            Error::Date { source, .. } => Some(source),
+            Error::Estimate { .. } | Error::ForecastOverflow => None,
+            Error::Config(error) => Some(error),
+            Error::Keyword(error) => Some(error),
+            Error::Graph(error) => Some(error),
+        }
+    }
+}
+
+impl std::fmt::Display for GraphError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GraphError::Cycles { cycles } => {
+                if let [urls] = cycles.as_slice() {
+                    write!(
+                        f,
+                        "Dependency cycle, where each item blocks the next: "
+                    )?;
+                    return fmt_cycle(f, urls);
+                }
+                write!(
+                    f,
+                    "{} dependency cycles, where each item blocks the next:",
+                    cycles.len()
+                )?;
+                for urls in cycles {
+                    write!(f, "\n  ")?;
+                    fmt_cycle(f, urls)?;
+                }
+                Ok(())
+            }
+            GraphError::NotFound { reference } => {
+                write!(f, "No item found for {reference:?}")
+            }
+            GraphError::Ambiguous { reference, urls } => {
+                write!(
+                    f,
+                    "{reference:?} matches more than one item; use one of: {}",
+                    urls.join(", ")
+                )
+            }
+        }
+    }
+}
+
+/// Writes the cycle in blocking order, closing the loop.
+fn fmt_cycle(
+    f: &mut std::fmt::Formatter<'_>,
+    urls: &[String],
+) -> std::fmt::Result {
+    for url in urls.iter().rev() {
+        write!(f, "{url} -> ")?;
+    }
+    match urls.last() {
+        Some(url) => write!(f, "{url}"),
+        None => Ok(()),
+    }
+}
+
+impl std::error::Error for GraphError {}
+
+impl From<ConfigError> for Error {
+    fn from(error: ConfigError) -> Self {
+        Error::Config(error)
+    }
+}
+
+impl From<KeywordError> for Error {
+    fn from(error: KeywordError) -> Self {
+        Error::Keyword(error)
+    }
+}
+
+impl From<GraphError> for Error {
+    fn from(error: GraphError) -> Self {
+        Error::Graph(error)
+    }
+}
+
+/// Displays a path given by the user, where "-" means STDIN.
+fn display_path(path: &Path) -> std::path::Display<'_> {
+    if path == Path::new("-") {
+        Path::new("<stdin>").display()
+    } else {
+        path.display()
+    }
+}
@@ -0,0 +1,445 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::path::Path;
+
+use indexmap::IndexMap;
+use time::{Duration, OffsetDateTime};
+
+use crate::diagnostics::{Code, Diagnostics};
+use crate::error::{Error, Result};
+use crate::gantt::{Gantt, GanttEnd, GanttSection, GanttStart, GanttTask};
+use crate::graph::{Graph, Node};
+use crate::input;
+use crate::order::WorkOrder;
+use crate::url;
+use crate::util::{markdown_link, regex};
+
+/// The longest estimate in days.  Longer estimates are likely mistakes, and
+/// many of them can't be scheduled before the latest date that can be
+/// represented.
+pub const MAX_ESTIMATE_DAYS: f64 = 3650.0;
+
+/// Returns how long an estimate in days is, or `None` if it's negative, isn't
+/// finite, or is more than [`MAX_ESTIMATE_DAYS`].
+pub fn estimate_duration(days: f64) -> Option<Duration> {
+    if !(0.0..=MAX_ESTIMATE_DAYS).contains(&days) {
+        return None;
+    }
+    Duration::checked_seconds_f64(days * 86_400.0)
+}
+
+/// Estimates of how many days of work items take.
+#[derive(Debug, Clone, Default)]
+pub struct Estimates {
+    /// Estimates from a file, by canonical URL.
+    by_url: HashMap<String, f64>,
+    /// The estimate for items without one.
+    pub default: f64,
+    /// A custom Project field with estimates, e.g. "Size".
+    pub field: Option<String>,
+}
+
+impl Estimates {
+    pub fn new(default: f64) -> Self {
+        Self {
+            by_url: HashMap::new(),
+            default,
+            field: None,
+        }
+    }
+
+    /// Reads estimates from a TOML file that maps items to days, e.g.
+    /// `"owner/repo#123" = 3`.  Items can be given as anything
+    /// [`Graph::find`] accepts.  Items that aren't in the graph are reported
+    /// as diagnostics.  Fails if an estimate isn't valid; see
+    /// [`estimate_duration`].
+    pub fn load(
+        &mut self,
+        path: &Path,
+        graph: &Graph,
+        diagnostics: &mut Diagnostics,
+    ) -> Result<()> {
+        let text = input::read_input(path)?;
+        let table: IndexMap<String, f64> =
+            toml::from_str(&text).map_err(|source| Error::Toml {
+                path: path.to_owned(),
+                source,
+            })?;
+        for (reference, days) in table {
+            if estimate_duration(days).is_none() {
+                return Err(Error::Estimate {
+                    item: Some(reference),
+                    days,
+                });
+            }
+            match graph.find(&reference) {
+                Ok(node) => {
+                    self.by_url.insert(url::canonical(&node.url), days);
+                }
+                Err(error) => diagnostics.warn(
+                    Code::UnknownItem,
+                    format!("Ignored estimate. {error}"),
+                    None,
+                ),
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the estimate in days from the file, the Project field, or a
+    /// label like "size: 3", "estimate/0.5", or "points:2".  Field values and
+    /// labels that aren't valid estimates are ignored; see
+    /// [`estimate_duration`].
+    pub fn get(&self, node: &Node) -> Option<f64> {
+        if let Some(&days) = self.by_url.get(&url::canonical(&node.url)) {
+            return Some(days);
+        }
+        let is_valid = |&days: &f64| estimate_duration(days).is_some();
+        if let Some(days) = self
+            .field
+            .as_ref()
+            .and_then(|field| node.field(field)?.as_f64())
+            .filter(is_valid)
+        {
+            return Some(days);
+        }
+        node.labels.iter().find_map(|label| {
+            regex!(
+                r"(?i-u)^(?:size|estimate|points)[ :/=_-]*([0-9]+(?:\.[0-9]+)?)$"
+            )
+            .captures(label.trim())?
+            .get(1)?
+            .as_str()
+            .parse()
+            .ok()
+            .filter(is_valid)
+        })
+    }
+}
+
+/// Options for [`Forecast::new`].
+#[derive(Debug, Clone, Copy)]
+pub struct ForecastOptions {
+    /// How many issues can be worked on at once.
+    pub team_size: NonZeroUsize,
+    /// When work starts.
+    pub start: OffsetDateTime,
+}
+
+/// When an open issue is projected to be worked on.
+#[derive(Debug, Clone, Copy)]
+pub struct ScheduledTask<'g> {
+    pub node: &'g Node,
+    /// The index of the team member working on it.
+    pub worker: usize,
+    pub start: OffsetDateTime,
+    pub end: OffsetDateTime,
+}
+
+/// A projected schedule for the open issues.
+#[derive(Debug)]
+pub struct Forecast<'g> {
+    pub options: ForecastOptions,
+    /// The tasks in the order they start.
+    pub tasks: Vec<ScheduledTask<'g>>,
+    /// Indexes of tasks that no open issue depends on, i.e. the goals, in
+    /// the order they end.
+    pub goals: Vec<usize>,
+    /// How many issues had no estimate and used the default.
+    pub unestimated: usize,
+}
+
+impl<'g> Forecast<'g> {
+    /// Schedules open issues one at a time per team member, each after all of
+    /// its open prerequisites.  When there's a choice, the issue with the
+    /// most work after it goes first, which shortens the critical path.
+    /// Fails if there are dependency cycles, if the default estimate isn't
+    /// valid, or if the work ends after the latest date that can be
+    /// represented.
+    pub fn new(
+        graph: &'g Graph,
+        estimates: &Estimates,
+        options: ForecastOptions,
+    ) -> Result<Self> {
+        let order = WorkOrder::new(graph)?;
+        let nodes: Vec<&Node> = order.waves.iter().flatten().copied().collect();
+        let indexes: HashMap<&str, usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| (node.url.as_str(), index))
+            .collect();
+        let mut unestimated = 0;
+        let durations = nodes
+            .iter()
+            .map(|node| {
+                let days = estimates.get(node).unwrap_or_else(|| {
+                    unestimated += 1;
+                    estimates.default
+                });
+                // Estimates that aren't the default were already checked.
+                estimate_duration(days)
+                    .ok_or(Error::Estimate { item: None, days })
+            })
+            .collect::<Result<Vec<Duration>>>()?;
+        let mut dependents = vec![Vec::new(); nodes.len()];
+        let mut remaining = vec![0_usize; nodes.len()];
+        for (index, node) in nodes.iter().enumerate() {
+            for prerequisite in graph.dependencies(node) {
+                if let Some(&prerequisite) =
+                    indexes.get(prerequisite.url.as_str())
+                {
+                    dependents[prerequisite].push(index);
+                    remaining[index] += 1;
+                }
+            }
+        }
+
+        // The work on the longest path from each task to a goal.  Nodes are
+        // in dependency order, so dependents are done first in reverse.
+        let mut tail = vec![Duration::ZERO; nodes.len()];
+        for index in (0..nodes.len()).rev() {
+            let after = dependents[index]
+                .iter()
+                .map(|&dependent| tail[dependent])
+                .max()
+                .unwrap_or(Duration::ZERO);
+            tail[index] = durations[index] + after;
+        }
+
+        let mut tasks = Vec::with_capacity(nodes.len());
+        let mut task_of = vec![None; nodes.len()];
+        let mut ready: Vec<usize> = (0..nodes.len())
+            .filter(|&index| remaining[index] == 0)
+            .collect();
+        let mut free_workers: Vec<usize> =
+            (0..options.team_size.get()).collect();
+        // (end, node index, worker)
+        let mut running: Vec<(OffsetDateTime, usize, usize)> = Vec::new();
+        let mut now = options.start;
+        loop {
+            // Stable, so ties keep the work order.
+            ready.sort_by_key(|&index| Reverse(tail[index]));
+            while !free_workers.is_empty() && !ready.is_empty() {
+                let index = ready.remove(0);
+                let worker = free_workers.remove(0);
+                let end = now
+                    .checked_add(durations[index])
+                    .ok_or(Error::ForecastOverflow)?;
+                task_of[index] = Some(tasks.len());
+                tasks.push(ScheduledTask {
+                    node: nodes[index],
+                    worker,
+                    start: now,
+                    end,
+                });
+                running.push((end, index, worker));
+            }
+
+            let Some(next) = running.iter().map(|&(end, _, _)| end).min()
+            else {
+                break;
+            };
+            now = next;
+            running.retain(|&(end, index, worker)| {
+                if end > now {
+                    return true;
+                }
+                free_workers.push(worker);
+                for &dependent in &dependents[index] {
+                    remaining[dependent] -= 1;
+                    if remaining[dependent] == 0 {
+                        ready.push(dependent);
+                    }
+                }
+                false
+            });
+            free_workers.sort_unstable();
+        }
+
+        let mut goals: Vec<usize> = (0..nodes.len())
+            .filter(|&index| dependents[index].is_empty())
+            .filter_map(|index| task_of[index])
+            .collect();
+        goals.sort_by_key(|&task| tasks[task].end);
+
+        Ok(Self {
+            options,
+            tasks,
+            goals,
+            unestimated,
+        })
+    }
+
+    /// Returns a gantt chart with a section per team member.
+    pub fn gantt(&self) -> Gantt {
+        let mut sections: Vec<GanttSection> = (0..self.options.team_size.get())
+            .map(|worker| GanttSection {
+                name: format!("Person {}", worker + 1),
+                tasks: Vec::new(),
+            })
+            .collect();
+        for task in &self.tasks {
+            sections[task.worker].tasks.push(GanttTask {
+                id: format!("t{}", task.node.id),
+                name: task.node.text.clone(),
+                status: None,
+                start: GanttStart::At(task.start),
+                end: GanttEnd::At(task.end),
+                url: task.node.link().map(str::to_owned),
+            });
+        }
+        sections.retain(|section| !section.tasks.is_empty());
+
+        Gantt {
+            title: "Forecast".to_owned(),
+            sections,
+        }
+    }
+}
+
+/// Formats the projected goal dates as Markdown, followed by the gantt chart.
+impl std::fmt::Display for Forecast<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let team_size = self.options.team_size.get();
+        write!(
+            f,
+            "Forecast for {team_size} {} starting {}.",
+            if team_size == 1 { "person" } else { "people" },
+            self.options.start.date()
+        )?;
+        if self.unestimated != 0 {
+            write!(
+                f,
+                "  {} of {} issues have no estimate.",
+                self.unestimated,
+                self.tasks.len()
+            )?;
+        }
+        writeln!(f)?;
+        writeln!(f)?;
+        if self.tasks.is_empty() {
+            return writeln!(f, "No open issues.");
+        }
+
+        writeln!(f, "| Goal | Projected completion |")?;
+        writeln!(f, "| --- | --- |")?;
+        for &index in &self.goals {
+            let task = &self.tasks[index];
+            writeln!(
+                f,
+                "| {} | {} |",
+                markdown_link(task.node).replace('|', "\\|"),
+                task.end.date()
+            )?;
+        }
+        writeln!(f)?;
+        writeln!(f, "```mermaid")?;
+        write!(f, "{}", self.gantt())?;
+        writeln!(f, "```")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::clock::parse_date;
+    use crate::graph::test_util::{graph, issue, label, with};
+
+    #[test]
+    fn test_forecast() {
+        let sized = |number, size, body| {
+            with(
+                issue(number, "OPEN", body),
+                json!({"labels": [label(size)]}),
+            )
+        };
+        // 1 needs 2 and 3, and 3 needs 4.  5 is on its own.
+        let graph = graph([
+            sized(1, "size: 1", "Depends on: #2\nDepends on: #3"),
+            sized(2, "size: 2", ""),
+            sized(3, "size: 1", "Depends on: #4"),
+            sized(4, "size: 2", ""),
+            issue(5, "OPEN", ""),
+        ]);
+        let start = parse_date("2024-01-01").unwrap();
+        let forecast = |team_size| {
+            let options = ForecastOptions {
+                team_size: NonZeroUsize::new(team_size).unwrap(),
+                start,
+            };
+            Forecast::new(&graph, &Estimates::new(0.5), options).unwrap()
+        };
+        let ends = |forecast: &Forecast<'_>| {
+            forecast
+                .goals
+                .iter()
+                .map(|&index| {
+                    let task = forecast.tasks[index];
+                    (
+                        task.node.text.clone(),
+                        (task.end - start).as_seconds_f64(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let day = 86_400.0;
+
+        // One person does everything in order of the longest path: 4, 2, 3,
+        // 1, then 5.
+        let one = forecast(1);
+        assert_eq!(one.unestimated, 1);
+        assert_eq!(
+            ends(&one),
+            [
+                ("Issue 1".to_owned(), 6.0 * day),
+                ("Issue 5".to_owned(), 6.5 * day)
+            ]
+        );
+
+        // Two people do 4 and 2 at once, then 3 and 5, then 1.
+        let two = forecast(2);
+        assert_eq!(
+            ends(&two),
+            [
+                ("Issue 5".to_owned(), 2.5 * day),
+                ("Issue 1".to_owned(), 4.0 * day)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_invalid_estimates() {
+        let graph = graph([with(
+            issue(1, "OPEN", ""),
+            json!({"labels": [label("size: 99999999")]}),
+        )]);
+        let node = graph.nodes.values().next().unwrap();
+        let options = ForecastOptions {
+            team_size: NonZeroUsize::MIN,
+            start: parse_date("2024-01-01").unwrap(),
+        };
+
+        // The label is too large, so the default is used.
+        assert_eq!(Estimates::new(2.0).get(node), None);
+
+        for days in [f64::INFINITY, f64::NAN, -1.0, 4_000_000.0] {
+            let error = Forecast::new(&graph, &Estimates::new(days), options)
+                .unwrap_err();
+            assert!(
+                matches!(error, Error::Estimate { item: None, .. }),
+                "{error}"
+            );
+        }
+
+        let options = ForecastOptions {
+            start: parse_date("9999-12-31").unwrap(),
+            ..options
+        };
+        let error =
+            Forecast::new(&graph, &Estimates::new(2.0), options).unwrap_err();
+        assert!(matches!(error, Error::ForecastOverflow), "{error}");
+    }
+}
@@ -0,0 +1,184 @@
+use time::OffsetDateTime;
+
+use crate::chart::mermaid_quote;
+
+/// A Mermaid gantt chart.
+#[derive(Debug, Clone, Default)]
+pub struct Gantt {
+    pub title: String,
+    pub sections: Vec<GanttSection>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GanttSection {
+    pub name: String,
+    pub tasks: Vec<GanttTask>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GanttTask {
+    /// Used by other tasks to start after this one.  Must be unique in the
+    /// chart.
+    pub id: String,
+    pub name: String,
+    pub status: Option<GanttStatus>,
+    pub start: GanttStart,
+    pub end: GanttEnd,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GanttStatus {
+    Done,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GanttStart {
+    At(OffsetDateTime),
+    /// After all of the tasks with these IDs end.
+    After(Vec<String>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GanttEnd {
+    At(OffsetDateTime),
+    /// A duration after the start.
+    Duration(time::Duration),
+}
+
+impl std::fmt::Display for Gantt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "gantt")?;
+        if !self.title.is_empty() {
+            writeln!(f, "  title {}", gantt_escape(&self.title))?;
+        }
+        writeln!(f, "  dateFormat YYYY-MM-DD HH:mm")?;
+        writeln!(f, "  axisFormat %Y-%m-%d")?;
+
+        for section in &self.sections {
+            writeln!(f, "  section {}", gantt_escape(&section.name))?;
+            for task in &section.tasks {
+                write!(f, "  {} :", gantt_escape(&task.name))?;
+                match task.status {
+                    Some(GanttStatus::Done) => write!(f, "done, ")?,
+                    None => {}
+                }
+                write!(f, "{}, ", task.id)?;
+                match &task.start {
+                    GanttStart::At(start) => fmt_date_time(f, *start)?,
+                    GanttStart::After(ids) => {
+                        write!(f, "after {}", ids.join(" "))?
+                    }
+                }
+                write!(f, ", ")?;
+                match task.end {
+                    GanttEnd::At(end) => fmt_date_time(f, end)?,
+                    GanttEnd::Duration(duration) => {
+                        // Mermaid doesn't allow a zero duration.
+                        let minutes = duration.whole_minutes().max(1);
+                        if minutes % (24 * 60) == 0 {
+                            write!(f, "{}d", minutes / (24 * 60))?;
+                        } else if minutes % 60 == 0 {
+                            write!(f, "{}h", minutes / 60)?;
+                        } else {
+                            write!(f, "{minutes}m")?;
+                        }
+                    }
+                }
+                writeln!(f)?;
+            }
+        }
+
+        for task in self.sections.iter().flat_map(|section| &section.tasks) {
+            if let Some(url) = &task.url {
+                writeln!(f, "  click {} href {}", task.id, mermaid_quote(url))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes a date time in the chart's date format, in UTC.
+fn fmt_date_time(
+    f: &mut std::fmt::Formatter<'_>,
+    date_time: OffsetDateTime,
+) -> std::fmt::Result {
+    let date_time = date_time.to_offset(time::UtcOffset::UTC);
+    write!(
+        f,
+        "{} {:02}:{:02}",
+        date_time.date(),
+        date_time.hour(),
+        date_time.minute()
+    )
+}
+
+/// Escapes characters that end a task name or start a comment.
+fn gantt_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '#' => escaped.push_str("#35;"),
+            ':' => escaped.push_str("#58;"),
+            ';' => escaped.push_str("#59;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::parse_date;
+
+    #[test]
+    fn test_gantt() {
+        let start = parse_date("2024-01-01").unwrap();
+        let gantt = Gantt {
+            title: "Plan".to_owned(),
+            sections: vec![GanttSection {
+                name: "foo/bar".to_owned(),
+                tasks: vec![
+                    GanttTask {
+                        id: "t1".to_owned(),
+                        name: "Fix: #1; done".to_owned(),
+                        status: Some(GanttStatus::Done),
+                        start: GanttStart::At(start),
+                        end: GanttEnd::At(start + time::Duration::hours(36)),
+                        url: Some(
+                            "https://github.com/foo/bar/issues/1".to_owned(),
+                        ),
+                    },
+                    GanttTask {
+                        id: "t2".to_owned(),
+                        name: "Next".to_owned(),
+                        status: None,
+                        start: GanttStart::After(vec!["t1".to_owned()]),
+                        end: GanttEnd::Duration(time::Duration::days(2)),
+                        url: Some(
+                            "https://example.com/tasks?name=\"next\""
+                                .to_owned(),
+                        ),
+                    },
+                ],
+            }],
+        };
+
+        assert_eq!(
+            gantt.to_string(),
+            "\
+gantt
+  title Plan
+  dateFormat YYYY-MM-DD HH:mm
+  axisFormat %Y-%m-%d
+  section foo/bar
+  Fix#58; #35;1#59; done :done, t1, 2024-01-01 00:00, 2024-01-02 12:00
+  Next :t2, after t1, 2d
+  click t1 href \"https://github.com/foo/bar/issues/1\"
+  click t2 href \"https://example.com/tasks?name=#quot;next#quot;\"
+"
+        );
+    }
+}
@@ -1,27 +1,33 @@
+use std::borrow::Cow;
 use std::num::NonZeroU32;
 
+use indexmap::IndexMap;
 use time::OffsetDateTime;
 
-type GithubId = String;
-type GithubNumber = NonZeroU32;
+use crate::clock;
+use crate::parse::{Relation, RelationKind};
+
+pub type GithubId = String;
+pub type GithubNumber = NonZeroU32;
 
-#[allow(unused)]
 #[derive(Debug, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub(crate) struct GithubIssue {
+pub struct GithubIssue {
     #[serde(default)]
     pub assignees: Option<Vec<GithubIssueAssignee>>,
     #[serde(default)]
     pub body: String,
     pub closed: bool,
+    #[serde(default, deserialize_with = "deserialize_optional_rfc3339")]
+    pub closed_at: Option<OffsetDateTime>,
     #[serde(default)]
     pub comments: Option<Vec<GithubIssueComment>>,
-    #[allow(unused)]
+    #[serde(default, deserialize_with = "deserialize_optional_rfc3339")]
+    pub created_at: Option<OffsetDateTime>,
     pub id: GithubId,
-    #[allow(unused)]
     pub labels: Vec<GithubLabel>,
-    // TODO: Add milestone.
-    // pub milestone: Option<_>,
+    #[serde(default)]
+    pub milestone: Option<GithubMilestone>,
     /// The issue or PR number that you use to reference it, e.g. #123.
     pub number: GithubNumber,
     pub project_items: Vec<GithubIssueProjectItem>,
@@ -30,6 +36,24 @@ pub(crate) struct GithubIssue {
     #[serde(deserialize_with = "deserialize_rfc3339")]
     pub updated_at: OffsetDateTime,
     pub url: String,
+    /// Native sub-issues of this issue.
+    #[serde(default, deserialize_with = "deserialize_issue_refs")]
+    pub sub_issues: Vec<GithubIssueRef>,
+    /// The native parent issue when this is a sub-issue.
+    #[serde(default)]
+    pub parent: Option<GithubIssueRef>,
+    /// Issues tracked by this issue's task list.
+    #[serde(default, deserialize_with = "deserialize_issue_refs")]
+    pub tracked_issues: Vec<GithubIssueRef>,
+    /// Issues whose task list tracks this issue.
+    #[serde(default, deserialize_with = "deserialize_issue_refs")]
+    pub tracked_in_issues: Vec<GithubIssueRef>,
+    /// Issues that block this issue.
+    #[serde(default, deserialize_with = "deserialize_issue_refs")]
+    pub blocked_by: Vec<GithubIssueRef>,
+    /// Issues that this issue blocks.
+    #[serde(default, deserialize_with = "deserialize_issue_refs")]
+    pub blocking: Vec<GithubIssueRef>,
 }
 
 impl GithubIssue {
@@ -46,23 +70,134 @@ impl GithubIssue {
 
         None
     }
+
+    /// Returns the relations that GitHub tracks natively, outside of the body
+    /// text, e.g. sub-issues and issue dependencies.
+    pub fn relations(&self) -> impl Iterator<Item = Relation<'_>> {
+        let forward = [
+            (RelationKind::SubIssue, &self.sub_issues),
+            (RelationKind::Tracks, &self.tracked_issues),
+            (RelationKind::BlockedBy, &self.blocked_by),
+        ];
+        let reverse = [
+            (RelationKind::TrackedBy, &self.tracked_in_issues),
+            (RelationKind::Blocks, &self.blocking),
+        ];
+
+        forward
+            .into_iter()
+            .chain(reverse)
+            .flat_map(|(kind, refs)| {
+                refs.iter().map(move |issue_ref| Relation {
+                    kind,
+                    target: Cow::Borrowed(issue_ref.url.as_str()),
+                })
+            })
+            .chain(self.parent.iter().map(|issue_ref| Relation {
+                kind: RelationKind::Parent,
+                target: Cow::Borrowed(issue_ref.url.as_str()),
+            }))
+    }
+}
+
+/// A pull request as output by `gh pr list --json`.
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GithubPullRequest {
+    #[serde(default)]
+    pub assignees: Option<Vec<GithubIssueAssignee>>,
+    #[serde(default)]
+    pub body: String,
+    #[serde(default, deserialize_with = "deserialize_optional_rfc3339")]
+    pub closed_at: Option<OffsetDateTime>,
+    /// Issues that GitHub will close when this is merged.
+    #[serde(default, deserialize_with = "deserialize_issue_refs")]
+    pub closing_issues_references: Vec<GithubIssueRef>,
+    #[serde(default, deserialize_with = "deserialize_optional_rfc3339")]
+    pub created_at: Option<OffsetDateTime>,
+    pub id: GithubId,
+    #[serde(default)]
+    pub is_draft: bool,
+    #[serde(default)]
+    pub labels: Vec<GithubLabel>,
+    #[serde(default)]
+    pub milestone: Option<GithubMilestone>,
+    /// The PR number that you use to reference it, e.g. #123.
+    pub number: GithubNumber,
+    #[serde(default)]
+    pub project_items: Vec<GithubIssueProjectItem>,
+    pub state: GithubPullRequestState,
+    pub title: String,
+    #[serde(deserialize_with = "deserialize_rfc3339")]
+    pub updated_at: OffsetDateTime,
+    pub url: String,
+}
+
+impl GithubPullRequest {
+    /// Returns the repository part of the URL, e.g.
+    /// "https://github.com/owner/repo", if found.
+    pub fn repository(&self) -> Option<&str> {
+        self.url.split_once("/pull").map(|(repo, _)| repo)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GithubPullRequestState {
+    #[default]
+    Open,
+    Closed,
+    Merged,
+}
+
+impl GithubPullRequestState {
+    /// The state of the equivalent issue.  Merged PRs are closed.
+    pub fn issue_state(self) -> GithubIssueState {
+        match self {
+            GithubPullRequestState::Open => GithubIssueState::Open,
+            GithubPullRequestState::Closed | GithubPullRequestState::Merged => {
+                GithubIssueState::Closed
+            }
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for GithubPullRequestState {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s: String = serde::Deserialize::deserialize(deserializer)?;
+        match s.to_ascii_uppercase().as_str() {
+            "OPEN" => Ok(GithubPullRequestState::Open),
+            "CLOSED" => Ok(GithubPullRequestState::Closed),
+            "MERGED" => Ok(GithubPullRequestState::Merged),
+            _ => Err(serde::de::Error::custom(format!(
+                "Unexpected value: {s:?}"
+            ))),
+        }
+    }
+}
+
+/// A reference to another issue, as found in GitHub's relationship fields.
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GithubIssueRef {
+    #[serde(default)]
+    pub number: Option<GithubNumber>,
+    pub url: String,
 }
 
-#[allow(unused)]
 #[derive(Debug, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub(crate) struct GithubIssueAssignee {
-    #[allow(unused)]
+pub struct GithubIssueAssignee {
     pub id: GithubId,
     pub login: String,
     pub name: String,
 }
 
-#[allow(unused)]
 #[derive(Debug, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub(crate) struct GithubIssueComment {
-    #[allow(unused)]
+pub struct GithubIssueComment {
     pub id: GithubId,
     pub author: GithubIssueCommentAuthor,
     pub author_association: String,
@@ -77,51 +212,165 @@ pub(crate) struct GithubIssueComment {
     pub viewer_did_author: bool,
 }
 
-#[allow(unused)]
 #[derive(Debug, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub(crate) struct GithubIssueCommentAuthor {
+pub struct GithubIssueCommentAuthor {
     pub login: String,
 }
 
 #[derive(Debug, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub(crate) struct GithubLabel {
-    #[allow(unused)]
+pub struct GithubLabel {
     pub id: GithubId,
-    #[allow(unused)]
     pub name: String,
     #[serde(default)]
-    #[allow(unused)]
     pub description: String,
     /// Hex color without the # prefix.
-    #[allow(unused)]
     pub color: String,
 }
 
-#[allow(unused)]
 #[derive(Debug, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub(crate) struct GithubIssueProjectItem {
+pub struct GithubMilestone {
+    #[serde(default)]
+    pub number: Option<GithubNumber>,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default, deserialize_with = "deserialize_optional_rfc3339")]
+    pub due_on: Option<OffsetDateTime>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GithubIssueProjectItem {
     /// The status field of the project item.  Since Projects are customizable,
     /// it's possible for a project to not have a status field.
     #[serde(default)]
     pub status: Option<GithubIssueProjectItemStatus>,
     /// The title of the Project that this is an item of.
     pub title: String,
+    /// Custom fields, by field name, e.g. "Priority" or "Target Date".
+    /// Values that aren't one of the supported types are left out.
+    #[serde(flatten, deserialize_with = "deserialize_field_values")]
+    pub fields: IndexMap<String, GithubProjectFieldValue>,
 }
 
-#[allow(unused)]
+/// An item as output by `gh project item-list --format json`.  Unlike
+/// [`GithubIssueProjectItem`], this is the item itself, with the issue, pull
+/// request, or draft issue as its content.
 #[derive(Debug, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub(crate) struct GithubIssueProjectItemStatus {
+pub struct GithubProjectItem {
+    /// The project item's ID, which is different from the content's ID.
+    pub id: GithubId,
+    #[serde(default)]
+    pub assignees: Vec<String>,
+    pub content: GithubProjectItemContent,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    #[serde(default)]
+    pub milestone: Option<GithubMilestone>,
+    /// The repository's URL, e.g. "https://github.com/owner/repo".  Draft
+    /// issues don't have one.
+    #[serde(default)]
+    pub repository: Option<String>,
+    /// The name of the status field's option, e.g. "Todo" or "Done".
+    #[serde(default)]
+    pub status: Option<String>,
+    pub title: String,
+    /// Custom fields, by field name.
+    #[serde(flatten, deserialize_with = "deserialize_field_values")]
+    pub fields: IndexMap<String, GithubProjectFieldValue>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GithubProjectItemContent {
+    #[serde(rename = "type")]
+    pub kind: GithubProjectItemKind,
+    #[serde(default)]
+    pub body: String,
+    #[serde(default)]
+    pub number: Option<GithubNumber>,
+    pub title: String,
+    /// Draft issues don't have a URL.
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize)]
+pub enum GithubProjectItemKind {
+    Issue,
+    PullRequest,
+    DraftIssue,
+}
+
+/// The value of a custom Project field.  Exports don't say what type a field
+/// is, so it's inferred from the shape of the value.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(untagged)]
+pub enum GithubProjectFieldValue {
+    Number(f64),
+    Iteration(GithubProjectIteration),
+    SingleSelect {
+        #[serde(default, rename = "optionId")]
+        option_id: Option<GithubId>,
+        name: String,
+    },
+    /// A date without a time, e.g. "2024-01-31".
+    Date(#[serde(deserialize_with = "deserialize_date")] time::Date),
+    /// Text, or the name of a single-select option when exported as a
+    /// string.
+    Text(String),
+}
+
+impl GithubProjectFieldValue {
+    /// Returns the value as text, e.g. the name of a single-select option.
+    /// Numbers and dates aren't converted.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            GithubProjectFieldValue::Text(text) => Some(text),
+            GithubProjectFieldValue::SingleSelect { name, .. } => Some(name),
+            GithubProjectFieldValue::Iteration(iteration) => {
+                Some(&iteration.title)
+            }
+            GithubProjectFieldValue::Number(_)
+            | GithubProjectFieldValue::Date(_) => None,
+        }
+    }
+
+    /// Returns the value as a number, including text like "3".
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            GithubProjectFieldValue::Number(number) => Some(*number),
+            _ => self.as_str()?.trim().parse().ok(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GithubProjectIteration {
+    pub title: String,
+    #[serde(deserialize_with = "deserialize_date")]
+    pub start_date: time::Date,
+    /// The length in days.
+    pub duration: u32,
+    #[serde(default)]
+    pub iteration_id: Option<GithubId>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GithubIssueProjectItemStatus {
     pub option_id: GithubId,
     /// The name of the status enum, e.g. "Not Started", "In Progress" or "Done".
     pub name: String,
 }
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
-pub(crate) enum GithubIssueState {
+pub enum GithubIssueState {
     #[default]
     Open,
     Closed,
@@ -157,3 +406,131 @@ where
         ))
     })
 }
+
+fn deserialize_date<'de, D>(deserializer: D) -> Result<time::Date, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s: String = serde::Deserialize::deserialize(deserializer)?;
+    clock::parse_calendar_date(&s).map_err(|err| {
+        serde::de::Error::custom(format!("Failed to parse date: {err}"))
+    })
+}
+
+/// Deserializes the remaining keys of a project item as custom field values,
+/// skipping values of other types, e.g. `null` for an empty field.
+fn deserialize_field_values<'de, D>(
+    deserializer: D,
+) -> Result<IndexMap<String, GithubProjectFieldValue>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum MaybeFieldValue {
+        Value(GithubProjectFieldValue),
+        Other(serde::de::IgnoredAny),
+    }
+
+    let values: IndexMap<String, MaybeFieldValue> =
+        serde::Deserialize::deserialize(deserializer)?;
+    Ok(values
+        .into_iter()
+        .filter_map(|(name, value)| match value {
+            MaybeFieldValue::Value(value) => Some((name, value)),
+            MaybeFieldValue::Other(_) => None,
+        })
+        .collect())
+}
+
+/// Deserializes a list of issue references.  GitHub's GraphQL API wraps lists
+/// in a connection object, e.g. `{"nodes": [...]}`, whereas the CLI flattens
+/// them into an array, so accept either.  `null` is treated as empty.
+fn deserialize_issue_refs<'de, D>(
+    deserializer: D,
+) -> Result<Vec<GithubIssueRef>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum IssueRefs {
+        List(Vec<GithubIssueRef>),
+        Connection { nodes: Vec<GithubIssueRef> },
+    }
+
+    let refs: Option<IssueRefs> =
+        serde::Deserialize::deserialize(deserializer)?;
+    Ok(match refs {
+        Some(IssueRefs::List(refs) | IssueRefs::Connection { nodes: refs }) => {
+            refs
+        }
+        None => Vec::new(),
+    })
+}
+
+fn deserialize_optional_rfc3339<'de, D>(
+    deserializer: D,
+) -> Result<Option<OffsetDateTime>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s: Option<String> = serde::Deserialize::deserialize(deserializer)?;
+    s.filter(|s| !s.is_empty())
+        .map(|s| {
+            let format = time::format_description::well_known::Rfc3339;
+            OffsetDateTime::parse(&s, &format).map_err(|err| {
+                serde::de::Error::custom(format!(
+                    "Failed to parse RFC 3339 date time: {err}"
+                ))
+            })
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::graph::test_util::{issue, issue_url, with};
+
+    #[test]
+    fn test_relations() {
+        let issue_ref =
+            |number| json!({"number": number, "url": issue_url(number)});
+        let related: GithubIssue = serde_json::from_value(with(
+            issue(1, "OPEN", ""),
+            json!({
+                // As `gh issue list` prints it.
+                "subIssues": [issue_ref(2)],
+                // As the GraphQL API returns it.
+                "trackedIssues": {"nodes": [issue_ref(3)]},
+                "blockedBy": null,
+                "blocking": [issue_ref(4)],
+                "trackedInIssues": {"nodes": []},
+                "parent": {"url": issue_url(5)},
+            }),
+        ))
+        .unwrap();
+
+        let relations: Vec<_> = related
+            .relations()
+            .map(|relation| (relation.kind, relation.target.into_owned()))
+            .collect();
+        assert_eq!(
+            relations,
+            [
+                (RelationKind::SubIssue, issue_url(2)),
+                (RelationKind::Tracks, issue_url(3)),
+                (RelationKind::Blocks, issue_url(4)),
+                (RelationKind::Parent, issue_url(5)),
+            ]
+        );
+
+        // The fields are optional.
+        let unrelated: GithubIssue =
+            serde_json::from_value(issue(1, "OPEN", "")).unwrap();
+        assert_eq!(unrelated.relations().count(), 0);
+    }
+}
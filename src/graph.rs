@@ -0,0 +1,1346 @@
+use std::collections::HashMap;
+
+use indexmap::{IndexMap, IndexSet};
+use time::OffsetDateTime;
+
+use crate::diagnostics::{Code, Diagnostics};
+use crate::error::GraphError;
+use crate::github::{
+    GithubIssue, GithubIssueProjectItem, GithubIssueState,
+    GithubProjectFieldValue, GithubProjectItem, GithubProjectItemKind,
+    GithubPullRequest,
+};
+use crate::jira::JiraIssue;
+use crate::parse::{self, ParseOptions};
+use crate::task::Task;
+use crate::url;
+pub use crate::url::{DRAFT_URL_PREFIX, JIRA_URL_PREFIX, TASK_URL_PREFIX};
+
+#[cfg(test)]
+pub(crate) mod test_util;
+
+pub type NodeId = String;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeKind {
+    #[default]
+    Issue,
+    PullRequest,
+}
+
+#[derive(Debug, Clone)]
+pub struct Node {
+    /// Short ID that's unique within the graph, used in diagrams.
+    pub id: NodeId,
+    pub kind: NodeKind,
+    pub text: String,
+    pub url: String,
+    pub state: GithubIssueState,
+    pub labels: Vec<String>,
+    /// Logins of the assigned users.
+    pub assignees: Vec<String>,
+    pub project_titles: IndexSet<String>,
+    /// Custom Project field values, by field name.  When the item is in more
+    /// than one project, the first project's value is used.  Forecasts read
+    /// estimates from them; see [`crate::forecast::Estimates`].
+    pub fields: IndexMap<String, GithubProjectFieldValue>,
+    /// The title of the milestone the item is in.
+    pub milestone: Option<String>,
+    /// Canonical URLs of the items this depends on.
+    pub depends_on_urls: IndexSet<String>,
+    /// For pull requests, the issues that merging it closes.
+    pub closes_urls: IndexSet<String>,
+    pub blocks_count: u32,
+    pub created_at: Option<OffsetDateTime>,
+    pub closed_at: Option<OffsetDateTime>,
+    /// When the item was last updated, or `None` if the input doesn't say,
+    /// as for project items, tasks, and Jira issues.
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+impl Node {
+    pub fn is_open(&self) -> bool {
+        match self.state {
+            GithubIssueState::Open => true,
+            GithubIssueState::Closed => false,
+        }
+    }
+
+    /// Returns true if this is a draft issue, which only exists in a project.
+    pub fn is_draft(&self) -> bool {
+        self.url.starts_with(DRAFT_URL_PREFIX)
+    }
+
+    /// Returns the URL of the item's web page, if it has one.  Draft issues,
+    /// and tasks and Jira issues without a URL, don't.
+    pub fn link(&self) -> Option<&str> {
+        Some(self.url.as_str()).filter(|url| {
+            url.starts_with("https://") || url.starts_with("http://")
+        })
+    }
+
+    /// Returns the repository's name, e.g. "owner/repo", if found.
+    pub fn repository_name(&self) -> Option<&str> {
+        let (_, rest) = self.url.split_once("://")?;
+        let (_, path) = rest.split_once('/')?;
+        let mut segments = path.splitn(4, '/');
+        let owner = segments.next().filter(|owner| !owner.is_empty())?;
+        let repo = segments.next().filter(|repo| !repo.is_empty())?;
+        segments
+            .next()
+            .filter(|kind| matches!(*kind, "issues" | "pull"))?;
+        path.get(..owner.len() + 1 + repo.len())
+    }
+
+    /// Returns the issue or pull request number, if the URL is an issue or
+    /// pull request URL like "https://github.com/owner/repo/issues/123".
+    pub fn number(&self) -> Option<u32> {
+        let (_, rest) = self.url.split_once("://")?;
+        let (_, path) = rest.split_once('/')?;
+        let segments: Vec<&str> =
+            path.trim_end_matches('/').split('/').collect();
+        match segments[..] {
+            [owner, repo, "issues" | "pull", number]
+                if !owner.is_empty() && !repo.is_empty() =>
+            {
+                number.parse().ok()
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a custom Project field, ignoring case in the name.
+    pub fn field(&self, name: &str) -> Option<&GithubProjectFieldValue> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+    }
+
+    /// Returns a short reference like GitHub's, e.g. "owner/repo#123", or the
+    /// URL if it isn't a GitHub issue or pull request URL.
+    pub fn reference(&self) -> String {
+        match (self.repository_name(), self.number()) {
+            (Some(repository), Some(number)) => {
+                format!("{repository}#{number}")
+            }
+            _ => self.url.clone(),
+        }
+    }
+}
+
+/// Items and the dependencies between them.
+#[derive(Debug, Clone, Default)]
+pub struct Graph {
+    /// Nodes keyed by their canonical URL.  See [`crate::url::canonical`].
+    pub nodes: IndexMap<NodeId, Node>,
+}
+
+impl Graph {
+    /// Builds a graph from GitHub issues and pull requests.
+    pub fn from_github(
+        issues: impl IntoIterator<Item = GithubIssue>,
+        pull_requests: impl IntoIterator<Item = GithubPullRequest>,
+        options: &ParseOptions,
+        diagnostics: &mut Diagnostics,
+    ) -> Self {
+        let mut builder = GraphBuilder::new(options, diagnostics);
+        for issue in issues {
+            builder.add_issue(issue);
+        }
+        for pull_request in pull_requests {
+            builder.add_pull_request(pull_request);
+        }
+        builder.build()
+    }
+
+    /// Reconstructs the graph as it was at a past date.  Items created after
+    /// the date are omitted, and items closed after the date are open.
+    ///
+    /// Dependencies and other fields can't be reconstructed, so they're as of
+    /// when the items were exported.
+    pub fn at(
+        &self,
+        date: OffsetDateTime,
+        diagnostics: &mut Diagnostics,
+    ) -> Self {
+        let mut graph = Graph::default();
+
+        for (url, node) in &self.nodes {
+            if node.created_at.is_some_and(|created_at| created_at > date) {
+                continue;
+            }
+
+            let mut node = node.clone();
+            if !node.is_open() {
+                match node.closed_at {
+                    Some(closed_at) if closed_at > date => {
+                        node.state = GithubIssueState::Open;
+                        node.closed_at = None;
+                    }
+                    Some(_) => {}
+                    None => diagnostics.warn(
+                        Code::MissingDate,
+                        "Closed item has no closedAt date, so it's shown closed",
+                        Some(&node.url),
+                    ),
+                }
+            }
+            if node.updated_at.is_some_and(|updated_at| updated_at > date) {
+                // The last update we know of before the date.
+                node.updated_at = Some(
+                    node.closed_at
+                        .or(node.created_at)
+                        .unwrap_or(date)
+                        .min(date),
+                );
+            }
+
+            graph.nodes.insert(url.clone(), node);
+        }
+
+        graph.update_blocks_counts();
+        graph
+    }
+
+    /// Updates each node's count of the items in the graph that it blocks.
+    fn update_blocks_counts(&mut self) {
+        // Count the items that each item blocks.
+        let mut blocks: IndexMap<NodeId, u32> = IndexMap::default();
+        for node in self.nodes.values() {
+            for depends_on_url in &node.depends_on_urls {
+                if !self.nodes.contains_key(depends_on_url) {
+                    continue;
+                }
+                let previous_count =
+                    blocks.entry(depends_on_url.clone()).or_default();
+                *previous_count = previous_count.saturating_add(1);
+            }
+        }
+
+        // Update nodes to have the count of items they block.
+        for (url, node) in &mut self.nodes {
+            node.blocks_count = blocks.get(url).copied().unwrap_or_default();
+        }
+    }
+
+    /// Looks up a node by any spelling of its URL.
+    pub fn get(&self, url: &str) -> Option<&Node> {
+        self.nodes.get(&url::canonical(url))
+    }
+
+    /// Finds a node by its URL, "owner/repo#123", or "#123".  The latter is
+    /// only found if exactly one repository has an item with the number.
+    pub fn find(&self, reference: &str) -> Result<&Node, GraphError> {
+        if let Some(node) = self.get(reference) {
+            return Ok(node);
+        }
+
+        let not_found = || GraphError::NotFound {
+            reference: reference.to_owned(),
+        };
+        let (repository, number) =
+            reference.trim().rsplit_once('#').ok_or_else(not_found)?;
+        let number = number.parse::<u32>().map_err(|_| not_found())?;
+        let matches: Vec<&Node> = self
+            .nodes
+            .values()
+            .filter(|node| {
+                node.number() == Some(number)
+                    && (repository.is_empty()
+                        || node.repository_name().is_some_and(|name| {
+                            name.eq_ignore_ascii_case(repository)
+                        }))
+            })
+            .collect();
+        match matches.as_slice() {
+            [] => Err(not_found()),
+            [node] => Ok(node),
+            _ => Err(GraphError::Ambiguous {
+                reference: reference.to_owned(),
+                urls: matches.iter().map(|node| node.url.clone()).collect(),
+            }),
+        }
+    }
+
+    /// Returns true if the node depends on an open node in the graph.
+    pub fn is_blocked(&self, node: &Node) -> bool {
+        self.dependencies(node).any(Node::is_open)
+    }
+
+    /// Returns the nodes that the node depends on and that are in the graph.
+    pub fn dependencies<'a>(
+        &'a self,
+        node: &'a Node,
+    ) -> impl Iterator<Item = &'a Node> + 'a {
+        node.depends_on_urls
+            .iter()
+            .filter_map(|url| self.nodes.get(url.as_str()))
+    }
+
+    /// Returns the nodes in the graph that depend on the node.
+    pub fn dependents<'a>(
+        &'a self,
+        node: &'a Node,
+    ) -> impl Iterator<Item = &'a Node> + 'a {
+        let url = url::canonical(&node.url);
+        self.nodes
+            .values()
+            .filter(move |other| other.depends_on_urls.contains(&url))
+    }
+}
+
+/// Builds a [`Graph`] one item at a time.
+#[derive(Debug)]
+pub struct GraphBuilder<'a> {
+    options: &'a ParseOptions,
+    diagnostics: &'a mut Diagnostics,
+    graph: Graph,
+    /// What the kept copy of each item adds once every item has been added,
+    /// keyed by the item's URL.
+    pending: IndexMap<NodeId, Pending>,
+    next_id: usize,
+}
+
+/// What a copy of an item adds to the graph besides its node, which is only
+/// added if the copy is kept.  See [`GraphBuilder::insert_node`].
+#[derive(Debug, Default)]
+struct Pending {
+    /// URLs of the items that depend on this one, from dependencies that are
+    /// declared on the prerequisite rather than on the dependent item.
+    dependents: IndexSet<String>,
+    /// Problems found in the copy.
+    diagnostics: Diagnostics,
+}
+
+impl<'a> GraphBuilder<'a> {
+    pub fn new(
+        options: &'a ParseOptions,
+        diagnostics: &'a mut Diagnostics,
+    ) -> Self {
+        Self {
+            options,
+            diagnostics,
+            graph: Graph::default(),
+            pending: IndexMap::default(),
+            next_id: 1,
+        }
+    }
+
+    pub fn add_issue(&mut self, issue: GithubIssue) {
+        let mut pending = Pending::default();
+        // Use a set to dedupe the dependencies.
+        let mut depends_on_urls = IndexSet::new();
+
+        if let Some(repository) = issue.repository() {
+            // Parse dependencies from the body text.
+            let dependencies = parse::relations(
+                issue.body.as_str(),
+                repository,
+                issue.url.as_str(),
+                self.options,
+                &mut pending.diagnostics,
+            )
+            .map(|relation| url::canonical(&relation.target));
+
+            depends_on_urls.extend(dependencies);
+        } else {
+            pending.diagnostics.warn(
+                Code::UnknownRepository,
+                "Unexpected issue URL; couldn't parse repository",
+                Some(&issue.url),
+            );
+        }
+
+        // Merge in relationships that GitHub tracks natively.
+        let key = url::canonical(&issue.url);
+        for relation in issue.relations() {
+            let target = url::canonical(&relation.target);
+            if relation.kind.is_reversed() {
+                pending.dependents.insert(target);
+            } else {
+                depends_on_urls.insert(target);
+            }
+        }
+
+        let project_titles = issue
+            .project_items
+            .iter()
+            .map(|item| item.title.clone())
+            .collect();
+
+        let node = Node {
+            // Assigned when inserted.
+            id: NodeId::new(),
+            kind: NodeKind::Issue,
+            text: issue.title,
+            url: issue.url,
+            state: issue.state,
+            labels: issue
+                .labels
+                .iter()
+                .map(|label| label.name.clone())
+                .collect(),
+            assignees: issue
+                .assignees
+                .iter()
+                .flatten()
+                .map(|assignee| assignee.login.clone())
+                .collect(),
+            project_titles,
+            fields: project_fields(&issue.project_items),
+            milestone: issue.milestone.map(|milestone| milestone.title),
+            depends_on_urls,
+            closes_urls: IndexSet::new(),
+            blocks_count: 0,
+            created_at: issue.created_at,
+            closed_at: issue.closed_at,
+            updated_at: Some(issue.updated_at),
+        };
+        self.insert_node(key, node, pending);
+    }
+
+    pub fn add_pull_request(&mut self, pull_request: GithubPullRequest) {
+        let mut pending = Pending::default();
+        let mut depends_on_urls = IndexSet::new();
+        let mut closes_urls: IndexSet<String> = pull_request
+            .closing_issues_references
+            .iter()
+            .map(|issue_ref| url::canonical(&issue_ref.url))
+            .collect();
+
+        if let Some(repository) = pull_request.repository() {
+            let body = pull_request.body.as_str();
+            let dependencies = parse::relations(
+                body,
+                repository,
+                pull_request.url.as_str(),
+                self.options,
+                &mut pending.diagnostics,
+            )
+            .map(|relation| url::canonical(&relation.target));
+            depends_on_urls.extend(dependencies);
+
+            let closes =
+                parse::closing_references(body, repository, self.options)
+                    .map(|relation| url::canonical(&relation.target));
+            closes_urls.extend(closes);
+        } else {
+            pending.diagnostics.warn(
+                Code::UnknownRepository,
+                "Unexpected pull request URL; couldn't parse repository",
+                Some(&pull_request.url),
+            );
+        }
+
+        let project_titles = pull_request
+            .project_items
+            .iter()
+            .map(|item| item.title.clone())
+            .collect();
+
+        let node = Node {
+            // Assigned when inserted.
+            id: NodeId::new(),
+            kind: NodeKind::PullRequest,
+            text: pull_request.title,
+            url: pull_request.url,
+            state: pull_request.state.issue_state(),
+            labels: pull_request
+                .labels
+                .iter()
+                .map(|label| label.name.clone())
+                .collect(),
+            assignees: pull_request
+                .assignees
+                .iter()
+                .flatten()
+                .map(|assignee| assignee.login.clone())
+                .collect(),
+            project_titles,
+            fields: project_fields(&pull_request.project_items),
+            milestone: pull_request.milestone.map(|milestone| milestone.title),
+            depends_on_urls,
+            closes_urls,
+            blocks_count: 0,
+            created_at: pull_request.created_at,
+            closed_at: pull_request.closed_at,
+            updated_at: Some(pull_request.updated_at),
+        };
+        self.insert_node(url::canonical(&node.url), node, pending);
+    }
+
+    /// Adds an item from a project export.  If the issue or pull request was
+    /// already added, the project and its field values are merged into it.
+    /// Otherwise, a node is added with what the export has, which doesn't
+    /// include dates or whether it's closed, so the item is closed if its
+    /// status is "Done".  Draft issues get a made-up URL; see
+    /// [`DRAFT_URL_PREFIX`].
+    pub fn add_project_item(
+        &mut self,
+        item: GithubProjectItem,
+        project_title: Option<&str>,
+    ) {
+        let content = item.content;
+        let url = match (&content.url, content.kind) {
+            (Some(url), _) if !url.is_empty() => url.clone(),
+            (_, GithubProjectItemKind::DraftIssue) => {
+                format!("{DRAFT_URL_PREFIX}{}", item.id)
+            }
+            _ => {
+                self.diagnostics.warn(
+                    Code::InvalidItem,
+                    format!(
+                        "Skipped project item {:?} without a URL",
+                        content.title
+                    ),
+                    None,
+                );
+                return;
+            }
+        };
+        let key = url::canonical(&url);
+
+        let mut fields = item.fields;
+        if let Some(status) = &item.status {
+            fields.entry("Status".to_owned()).or_insert_with(|| {
+                GithubProjectFieldValue::Text(status.clone())
+            });
+        }
+
+        if let Some(node) = self.graph.nodes.get_mut(&key) {
+            if let Some(title) = project_title {
+                node.project_titles.insert(title.to_owned());
+            }
+            for (name, value) in fields {
+                node.fields.entry(name).or_insert(value);
+            }
+            return;
+        }
+
+        // Draft issues aren't in a repository, so only absolute references
+        // can be resolved.
+        let mut pending = Pending::default();
+        let repository = item.repository.as_deref().unwrap_or_default();
+        let depends_on_urls = parse::relations(
+            &content.body,
+            repository,
+            &url,
+            self.options,
+            &mut pending.diagnostics,
+        )
+        .map(|relation| url::canonical(&relation.target))
+        .collect();
+
+        let done = item
+            .status
+            .as_deref()
+            .is_some_and(|status| status.eq_ignore_ascii_case("done"));
+        let node = Node {
+            // Assigned when inserted.
+            id: NodeId::new(),
+            kind: match content.kind {
+                GithubProjectItemKind::PullRequest => NodeKind::PullRequest,
+                GithubProjectItemKind::Issue
+                | GithubProjectItemKind::DraftIssue => NodeKind::Issue,
+            },
+            text: content.title,
+            url,
+            state: if done {
+                GithubIssueState::Closed
+            } else {
+                GithubIssueState::Open
+            },
+            labels: item.labels,
+            assignees: item.assignees,
+            project_titles: project_title
+                .map(str::to_owned)
+                .into_iter()
+                .collect(),
+            fields,
+            milestone: item.milestone.map(|milestone| milestone.title),
+            depends_on_urls,
+            closes_urls: IndexSet::new(),
+            blocks_count: 0,
+            created_at: None,
+            closed_at: None,
+            updated_at: None,
+        };
+        self.insert_node(key, node, pending);
+    }
+
+    /// Adds tasks from task files.  Tasks without a URL get a made-up one;
+    /// see [`TASK_URL_PREFIX`].  Since tasks refer to each other by ID, add
+    /// them all at once.  A task that's already in the graph, e.g. because
+    /// its URL is an issue's, is merged like a duplicate issue.
+    pub fn add_tasks(&mut self, tasks: impl IntoIterator<Item = Task>) {
+        let tasks: Vec<Task> = tasks.into_iter().collect();
+        let urls: HashMap<&str, String> = tasks
+            .iter()
+            .map(|task| (task.id.as_str(), task_url(task)))
+            .collect();
+
+        for task in &tasks {
+            let url = task_url(task);
+            let key = url::canonical(&url);
+            let mut pending = Pending::default();
+            let mut depends_on_urls = IndexSet::new();
+            for reference in &task.depends_on {
+                if let Some(target) = self.resolve_task_reference(
+                    reference,
+                    &urls,
+                    &url,
+                    &mut pending.diagnostics,
+                ) {
+                    depends_on_urls.insert(target);
+                }
+            }
+            for reference in &task.blocks {
+                if let Some(target) = self.resolve_task_reference(
+                    reference,
+                    &urls,
+                    &url,
+                    &mut pending.diagnostics,
+                ) {
+                    pending.dependents.insert(target);
+                }
+            }
+
+            let node = Node {
+                // Assigned when inserted.
+                id: NodeId::new(),
+                kind: NodeKind::Issue,
+                text: task.title.clone(),
+                url,
+                state: task.state,
+                labels: Vec::new(),
+                assignees: Vec::new(),
+                project_titles: IndexSet::new(),
+                fields: IndexMap::new(),
+                milestone: None,
+                depends_on_urls,
+                closes_urls: IndexSet::new(),
+                blocks_count: 0,
+                created_at: None,
+                closed_at: None,
+                updated_at: None,
+            };
+            self.insert_node(key, node, pending);
+        }
+    }
+
+    /// Returns the canonical URL that a task's reference is to: another task's
+    /// ID, `owner/repo#123`, or a URL.  A malformed reference is reported as a
+    /// diagnostic.
+    fn resolve_task_reference(
+        &self,
+        reference: &str,
+        urls: &HashMap<&str, String>,
+        context: &str,
+        diagnostics: &mut Diagnostics,
+    ) -> Option<String> {
+        let reference = reference.trim();
+        if let Some(url) = urls.get(reference) {
+            return Some(url::canonical(url));
+        }
+        // Tasks can be tracked anywhere, so any host is allowed.
+        if reference.contains("://") {
+            return Some(url::canonical(reference));
+        }
+        // Tasks aren't in a repository, so "#123" can't be resolved.
+        match parse::extract_url(reference, "", self.options) {
+            Some(url) => Some(url::canonical(&url)),
+            None => {
+                diagnostics.warn(
+                    Code::MalformedReference,
+                    format!(
+                        "Unknown task or malformed reference {reference:?}; use a task ID, owner/repo#123, or a URL"
+                    ),
+                    Some(context),
+                );
+                None
+            }
+        }
+    }
+
+    /// Adds an issue from a Jira CSV export.  Its URL is on the Jira site in
+    /// the parse options, or made up if there isn't one; see
+    /// [`ParseOptions::jira_issue_url`].  The export doesn't have dates in a
+    /// reliable format, so they're unknown.
+    pub fn add_jira_issue(&mut self, issue: JiraIssue) {
+        let url = self.options.jira_issue_url(&issue.key);
+        let key = url::canonical(&url);
+        let depends_on_urls = issue
+            .blocked_by
+            .iter()
+            .map(|blocker| {
+                url::canonical(&self.options.jira_issue_url(blocker))
+            })
+            .collect();
+        let mut pending = Pending::default();
+        for blocked in &issue.blocks {
+            pending
+                .dependents
+                .insert(url::canonical(&self.options.jira_issue_url(blocked)));
+        }
+
+        let node = Node {
+            // Assigned when inserted.
+            id: NodeId::new(),
+            kind: NodeKind::Issue,
+            state: if issue.is_done() {
+                GithubIssueState::Closed
+            } else {
+                GithubIssueState::Open
+            },
+            text: issue.summary,
+            url,
+            labels: issue.labels,
+            assignees: issue.assignee.into_iter().collect(),
+            project_titles: IndexSet::new(),
+            fields: IndexMap::new(),
+            milestone: None,
+            depends_on_urls,
+            closes_urls: IndexSet::new(),
+            blocks_count: 0,
+            created_at: None,
+            closed_at: None,
+            updated_at: None,
+        };
+        self.insert_node(key, node, pending);
+    }
+
+    /// Resolves dependencies between the items that were added.
+    pub fn build(mut self) -> Graph {
+        for (url, pending) in self.pending {
+            self.diagnostics.extend(pending.diagnostics);
+            for dependent in pending.dependents {
+                let Some(node) = self.graph.nodes.get_mut(&dependent) else {
+                    continue;
+                };
+                node.depends_on_urls.insert(url.clone());
+            }
+        }
+
+        self.graph.update_blocks_counts();
+
+        self.graph
+    }
+
+    /// Adds a node, or merges it into the node with the same URL, e.g. from
+    /// overlapping exports.  The copy that was updated most recently is kept,
+    /// with the projects of both, and the other copy's relations and
+    /// diagnostics are dropped.  Copies that say when they were updated are
+    /// preferred.  Copies that differ are reported.
+    fn insert_node(&mut self, key: NodeId, mut node: Node, pending: Pending) {
+        let Some(existing) = self.graph.nodes.get_mut(&key) else {
+            node.id = self.next_id();
+            self.graph.nodes.insert(key.clone(), node);
+            self.pending.insert(key, pending);
+            return;
+        };
+        let existing_pending = self.pending.entry(key).or_default();
+
+        let mut differences = Vec::new();
+        if existing.state != node.state {
+            differences.push("state");
+        }
+        if existing.text != node.text {
+            differences.push("title");
+        }
+        if existing.depends_on_urls != node.depends_on_urls
+            || existing_pending.dependents != pending.dependents
+        {
+            differences.push("dependencies");
+        }
+
+        node.id = existing.id.clone();
+        // A copy without a date, e.g. from a project export, doesn't replace
+        // one with a date, and of copies without dates, the first is kept.
+        let (newer, kept) = match (node.updated_at, existing.updated_at) {
+            (Some(updated_at), Some(existing_updated_at)) => (
+                updated_at > existing_updated_at,
+                "kept the one updated most recently",
+            ),
+            (Some(_), None) => (true, "kept the one with an update date"),
+            (None, Some(_)) => (false, "kept the one with an update date"),
+            (None, None) => {
+                (false, "kept the first one; neither has an update date")
+            }
+        };
+        if newer {
+            std::mem::swap(existing, &mut node);
+            *existing_pending = pending;
+        }
+        if !differences.is_empty() {
+            existing_pending.diagnostics.warn(
+                Code::ConflictingItem,
+                format!(
+                    "Found copies of the item with different {}; {kept}",
+                    differences.join(", ")
+                ),
+                Some(&existing.url),
+            );
+        }
+        existing.project_titles.extend(node.project_titles);
+        for (name, value) in node.fields {
+            existing.fields.entry(name).or_insert(value);
+        }
+    }
+
+    fn next_id(&mut self) -> NodeId {
+        let id = self.next_id.to_string();
+        self.next_id = self
+            .next_id
+            .checked_add(1)
+            .expect("Overflowed number of items");
+        id
+    }
+}
+
+/// Returns the task's URL, or a made-up one if it doesn't have one.
+fn task_url(task: &Task) -> String {
+    match &task.url {
+        Some(url) if !url.trim().is_empty() => url.trim().to_owned(),
+        _ => format!("{TASK_URL_PREFIX}{}", task.id),
+    }
+}
+
+/// Merges the custom field values of an item's projects.  The first project's
+/// value wins.
+fn project_fields(
+    project_items: &[GithubIssueProjectItem],
+) -> IndexMap<String, GithubProjectFieldValue> {
+    let mut fields = IndexMap::new();
+    for item in project_items {
+        for (name, value) in &item.fields {
+            fields.entry(name.clone()).or_insert_with(|| value.clone());
+        }
+    }
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::test_util::{github_issues, graph, issue, issue_url, with};
+    use super::*;
+    use crate::clock::parse_date;
+
+    #[test]
+    fn test_at() {
+        let issues = github_issues([
+            with(
+                issue(1, "OPEN", "Depends on: #2"),
+                json!({"createdAt": "2024-01-01T00:00:00Z",
+                       "updatedAt": "2024-03-01T00:00:00Z"}),
+            ),
+            with(
+                issue(2, "CLOSED", ""),
+                json!({"createdAt": "2024-01-01T00:00:00Z",
+                       "closedAt": "2024-02-01T00:00:00Z",
+                       "updatedAt": "2024-02-01T00:00:00Z"}),
+            ),
+            with(
+                issue(3, "OPEN", "Depends on: #1"),
+                json!({"createdAt": "2024-02-15T00:00:00Z",
+                       "updatedAt": "2024-02-15T00:00:00Z"}),
+            ),
+        ]);
+        let mut diagnostics = Diagnostics::new();
+        let graph = Graph::from_github(
+            issues,
+            [],
+            &ParseOptions::default(),
+            &mut diagnostics,
+        );
+        assert_eq!(
+            graph
+                .get("https://github.com/foo/bar/issues/1")
+                .unwrap()
+                .blocks_count,
+            1
+        );
+
+        let past =
+            graph.at(parse_date("2024-01-15").unwrap(), &mut diagnostics);
+
+        // Created after the date.
+        assert!(past.get("https://github.com/foo/bar/issues/3").is_none());
+        // Closed after the date.
+        let two = past.get("https://github.com/foo/bar/issues/2").unwrap();
+        assert!(two.is_open());
+        assert_eq!(two.blocks_count, 1);
+        // The only dependent was omitted.
+        let one = past.get("https://github.com/foo/bar/issues/1").unwrap();
+        assert_eq!(one.blocks_count, 0);
+        assert_eq!(one.updated_at, Some(parse_date("2024-01-01").unwrap()));
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_project_fields() {
+        let graph = graph([with(
+            issue(1, "OPEN", ""),
+            json!({"projectItems": [
+                {"title": "Roadmap",
+                 "status": {"optionId": "a", "name": "Todo"},
+                 "Priority": {"optionId": "b", "name": "P1"},
+                 "Size": 3, "Points": "5", "Target Date": "2024-03-31",
+                 "Iteration": {"title": "Sprint 2",
+                               "startDate": "2024-03-04", "duration": 14},
+                 "Notes": "Needs review", "Reviewers": null,
+                 "Linked pull requests": []},
+                {"title": "Other", "Size": 8}
+            ]}),
+        )]);
+        let node = graph.get("https://github.com/foo/bar/issues/1").unwrap();
+        let date = |date| parse_date(date).unwrap().date();
+
+        assert_eq!(
+            node.fields.keys().collect::<Vec<_>>(),
+            [
+                "Priority",
+                "Size",
+                "Points",
+                "Target Date",
+                "Iteration",
+                "Notes"
+            ]
+        );
+        assert_eq!(node.field("priority").unwrap().as_str(), Some("P1"));
+        // The first project's value wins.
+        assert_eq!(node.field("size").unwrap().as_f64(), Some(3.0));
+        assert_eq!(node.field("points").unwrap().as_f64(), Some(5.0));
+        assert_eq!(
+            node.field("target date"),
+            Some(&GithubProjectFieldValue::Date(date("2024-03-31")))
+        );
+        let Some(GithubProjectFieldValue::Iteration(iteration)) =
+            node.field("iteration")
+        else {
+            panic!("expected an iteration");
+        };
+        assert_eq!(iteration.start_date, date("2024-03-04"));
+        assert_eq!(iteration.duration, 14);
+        assert_eq!(node.field("notes").unwrap().as_str(), Some("Needs review"));
+    }
+
+    #[test]
+    fn test_project_items() {
+        let items = r#"[
+            {"id": "PVTI_1", "title": "Issue 1", "status": "Todo", "Size": 3,
+             "repository": "https://github.com/foo/bar",
+             "content": {"type": "Issue", "title": "Issue 1", "number": 1,
+                         "url": "https://github.com/foo/bar/issues/1"}},
+            {"id": "PVTI_2", "title": "Two", "status": "Done",
+             "repository": "https://github.com/foo/bar",
+             "labels": ["bug"], "assignees": ["octocat"],
+             "content": {"type": "Issue", "title": "Two", "number": 2,
+                         "body": "Depends on: #1",
+                         "url": "https://github.com/foo/bar/issues/2"}},
+            {"id": "PVTI_3", "title": "Launch", "status": "Todo",
+             "content": {"type": "DraftIssue", "title": "Launch",
+                         "body": "Depends on: foo/bar#2\nDepends on: #1"}}
+        ]"#;
+        let issues = github_issues([issue(1, "OPEN", "")]);
+        let items: Vec<GithubProjectItem> =
+            serde_json::from_str(items).unwrap();
+        let options = ParseOptions::default();
+        let mut diagnostics = Diagnostics::new();
+        let mut builder = GraphBuilder::new(&options, &mut diagnostics);
+        for issue in issues {
+            builder.add_issue(issue);
+        }
+        for item in items {
+            builder.add_project_item(item, Some("Roadmap"));
+        }
+        let graph = builder.build();
+
+        // Merged into the issue.
+        let one = graph.get("https://github.com/foo/bar/issues/1").unwrap();
+        assert_eq!(one.text, "Issue 1");
+        assert!(one.project_titles.contains("Roadmap"));
+        assert_eq!(one.field("size").unwrap().as_f64(), Some(3.0));
+        assert_eq!(one.field("status").unwrap().as_str(), Some("Todo"));
+        assert_eq!(one.blocks_count, 1);
+
+        // Only in the project.
+        let two = graph.get("https://github.com/foo/bar/issues/2").unwrap();
+        assert!(!two.is_open());
+        assert_eq!(two.labels, ["bug"]);
+        assert_eq!(two.assignees, ["octocat"]);
+
+        let draft = graph.get("draft:PVTI_3").unwrap();
+        assert!(draft.is_draft());
+        assert_eq!(draft.link(), None);
+        assert_eq!(
+            draft.depends_on_urls.iter().collect::<Vec<_>>(),
+            ["https://github.com/foo/bar/issues/2"]
+        );
+        // "#1" is relative to a repository, which drafts aren't in.
+        assert_eq!(diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn test_tasks() {
+        let task = |id: &str, depends_on: &[&str], blocks: &[&str]| Task {
+            id: id.to_owned(),
+            title: id.to_owned(),
+            state: GithubIssueState::Open,
+            url: None,
+            depends_on: depends_on.iter().map(|&s| s.to_owned()).collect(),
+            blocks: blocks.iter().map(|&s| s.to_owned()).collect(),
+        };
+        let contract = Task {
+            url: Some("https://example.com/contracts/1".to_owned()),
+            ..task("contract", &[], &[])
+        };
+        let approval = task(
+            "approval",
+            &["contract", "foo/bar#1", "#1"],
+            &["https://github.com/foo/bar/issues/2"],
+        );
+        let issues =
+            github_issues([issue(1, "OPEN", ""), issue(2, "OPEN", "")]);
+        let options = ParseOptions::default();
+        let mut diagnostics = Diagnostics::new();
+        let mut builder = GraphBuilder::new(&options, &mut diagnostics);
+        for issue in issues {
+            builder.add_issue(issue);
+        }
+        builder.add_tasks([contract, approval]);
+        let graph = builder.build();
+
+        let contract = graph.get("https://example.com/contracts/1").unwrap();
+        assert_eq!(contract.link(), Some("https://example.com/contracts/1"));
+        let approval = graph.get("task:approval").unwrap();
+        assert_eq!(approval.link(), None);
+        assert_eq!(
+            approval.depends_on_urls.iter().collect::<Vec<_>>(),
+            [
+                "https://example.com/contracts/1",
+                "https://github.com/foo/bar/issues/1"
+            ]
+        );
+        let two = graph.get("https://github.com/foo/bar/issues/2").unwrap();
+        assert_eq!(
+            two.depends_on_urls.iter().collect::<Vec<_>>(),
+            ["task:approval"]
+        );
+        assert_eq!(contract.blocks_count, 1);
+        // "#1" is relative to a repository, which tasks aren't in.
+        assert_eq!(diagnostics.len(), 1);
+    }
+
+    #[test]
+    fn test_jira_issues() {
+        let jira_issue = |key: &str, status_category: &str| JiraIssue {
+            key: key.to_owned(),
+            summary: key.to_owned(),
+            status_category: Some(status_category.to_owned()),
+            ..JiraIssue::default()
+        };
+        let plat_1 = JiraIssue {
+            blocked_by: vec!["PLAT-2".to_owned()],
+            ..jira_issue("PLAT-1", "In Progress")
+        };
+        let plat_3 = JiraIssue {
+            blocks: vec!["PLAT-1".to_owned()],
+            ..jira_issue("PLAT-3", "Done")
+        };
+        let issues = github_issues([issue(
+            1,
+            "OPEN",
+            "Depends on: PLAT-1\n- [ ] Support UTF-8",
+        )]);
+        let mut options = ParseOptions::default();
+        options.jira_url = Some("https://example.atlassian.net/".to_owned());
+        let mut diagnostics = Diagnostics::new();
+        let mut builder = GraphBuilder::new(&options, &mut diagnostics);
+        for issue in issues {
+            builder.add_issue(issue);
+        }
+        builder.add_jira_issue(plat_1);
+        builder.add_jira_issue(jira_issue("PLAT-2", "To Do"));
+        builder.add_jira_issue(plat_3);
+        let graph = builder.build();
+
+        // Keys are only recognized at the start of a reference.
+        let one = graph.get("https://github.com/foo/bar/issues/1").unwrap();
+        assert_eq!(
+            one.depends_on_urls.iter().collect::<Vec<_>>(),
+            ["https://example.atlassian.net/browse/PLAT-1"]
+        );
+        let plat_1 = graph
+            .get("https://example.atlassian.net/browse/PLAT-1")
+            .unwrap();
+        assert!(plat_1.is_open());
+        assert_eq!(
+            plat_1.depends_on_urls.iter().collect::<Vec<_>>(),
+            [
+                "https://example.atlassian.net/browse/PLAT-2",
+                "https://example.atlassian.net/browse/PLAT-3"
+            ]
+        );
+        let plat_3 = graph
+            .get("https://example.atlassian.net/browse/PLAT-3")
+            .unwrap();
+        assert!(!plat_3.is_open());
+        assert!(diagnostics.is_empty());
+
+        // Without a Jira URL, keys aren't recognized, and Jira issues don't
+        // have links.
+        let options = ParseOptions::default();
+        let mut diagnostics = Diagnostics::new();
+        let mut builder = GraphBuilder::new(&options, &mut diagnostics);
+        builder.add_jira_issue(jira_issue("PLAT-2", "To Do"));
+        let graph = builder.build();
+        assert_eq!(graph.get("jira:PLAT-2").unwrap().link(), None);
+    }
+
+    #[test]
+    fn test_native_relations() {
+        let issue_refs = |numbers: &[u32]| {
+            numbers
+                .iter()
+                .map(|&number| json!({"url": issue_url(number)}))
+                .collect::<Vec<_>>()
+        };
+        let graph = graph([
+            with(
+                // Also blocked by #2 in the body, which is only one edge.
+                issue(1, "OPEN", "Depends on: #2"),
+                json!({
+                    "blockedBy": issue_refs(&[2]),
+                    "subIssues": issue_refs(&[3]),
+                    "parent": {"url": issue_url(4)},
+                    "trackedInIssues": issue_refs(&[5]),
+                    "blocking": issue_refs(&[6]),
+                }),
+            ),
+            issue(2, "OPEN", ""),
+            issue(3, "OPEN", ""),
+            issue(4, "OPEN", ""),
+            issue(5, "OPEN", ""),
+            issue(6, "OPEN", ""),
+        ]);
+        let depends_on = |number| {
+            graph
+                .get(&issue_url(number))
+                .unwrap()
+                .depends_on_urls
+                .iter()
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(depends_on(1), [issue_url(2), issue_url(3)]);
+        // The parent, tracking issue, and blocked issue depend on #1.
+        for number in [4, 5, 6] {
+            assert_eq!(depends_on(number), [issue_url(1)]);
+        }
+        assert_eq!(graph.get(&issue_url(1)).unwrap().blocks_count, 3);
+        assert_eq!(graph.get(&issue_url(2)).unwrap().blocks_count, 1);
+    }
+
+    #[test]
+    fn test_duplicates() {
+        let copy = |state, project, updated_at| {
+            with(
+                issue(1, state, ""),
+                json!({"projectItems": [{"title": project}],
+                       "updatedAt": updated_at}),
+            )
+        };
+        let issues = github_issues([
+            copy("CLOSED", "B", "2024-03-01T00:00:00Z"),
+            copy("OPEN", "A", "2024-02-01T00:00:00Z"),
+            issue(2, "OPEN", ""),
+        ]);
+        let mut diagnostics = Diagnostics::new();
+        let graph = Graph::from_github(
+            issues,
+            [],
+            &ParseOptions::default(),
+            &mut diagnostics,
+        );
+
+        assert_eq!(graph.nodes.len(), 2);
+        let one = graph.get("https://github.com/foo/bar/issues/1").unwrap();
+        // The newer copy, even though it came first.
+        assert!(!one.is_open());
+        assert_eq!(one.id, "1");
+        assert_eq!(one.project_titles.iter().collect::<Vec<_>>(), ["B", "A"]);
+        let two = graph.get("https://github.com/foo/bar/issues/2").unwrap();
+        assert_eq!(two.id, "2");
+        let [conflict] = diagnostics.iter().collect::<Vec<_>>()[..] else {
+            panic!("Expected 1 diagnostic: {diagnostics:?}");
+        };
+        assert!(conflict
+            .message
+            .ends_with("kept the one updated most recently"));
+    }
+
+    #[test]
+    fn test_undated_copies() {
+        let task = Task {
+            id: "one".to_owned(),
+            title: "Task".to_owned(),
+            state: GithubIssueState::Closed,
+            url: Some(issue_url(1)),
+            depends_on: Vec::new(),
+            blocks: Vec::new(),
+        };
+        let options = ParseOptions::default();
+
+        // The issue's copy says when it was updated, so it's kept whichever
+        // comes first.
+        for task_first in [true, false] {
+            let mut diagnostics = Diagnostics::new();
+            let mut builder = GraphBuilder::new(&options, &mut diagnostics);
+            let issues = github_issues([issue(1, "OPEN", "")]);
+            if task_first {
+                builder.add_tasks([task.clone()]);
+            }
+            builder.add_issue(issues.into_iter().next().unwrap());
+            if !task_first {
+                builder.add_tasks([task.clone()]);
+            }
+            let graph = builder.build();
+
+            let one = graph.get(&issue_url(1)).unwrap();
+            assert_eq!(one.text, "Issue 1", "task first: {task_first}");
+            assert!(one.is_open());
+            let [conflict] = diagnostics.iter().collect::<Vec<_>>()[..] else {
+                panic!("Expected 1 diagnostic: {diagnostics:?}");
+            };
+            assert!(conflict
+                .message
+                .ends_with("kept the one with an update date"));
+        }
+
+        // Neither copy says when it was updated, so the first is kept.
+        let mut diagnostics = Diagnostics::new();
+        let mut builder = GraphBuilder::new(&options, &mut diagnostics);
+        let renamed = Task {
+            title: "Renamed".to_owned(),
+            ..task.clone()
+        };
+        builder.add_tasks([task, renamed]);
+        let graph = builder.build();
+
+        assert_eq!(graph.get(&issue_url(1)).unwrap().text, "Task");
+        let [conflict] = diagnostics.iter().collect::<Vec<_>>()[..] else {
+            panic!("Expected 1 diagnostic: {diagnostics:?}");
+        };
+        assert!(conflict
+            .message
+            .ends_with("kept the first one; neither has an update date"));
+    }
+
+    #[test]
+    fn test_duplicate_relations() {
+        let copy = |blocking, updated_at| {
+            with(
+                issue(1, "OPEN", "Depends on: nowhere"),
+                json!({"blocking": [{"url": issue_url(blocking)}],
+                       "updatedAt": updated_at}),
+            )
+        };
+        let issues = github_issues([
+            copy(2, "2024-02-01T00:00:00Z"),
+            // Newer, so it replaces the first copy.
+            copy(3, "2024-03-01T00:00:00Z"),
+            issue(2, "OPEN", ""),
+            issue(3, "OPEN", ""),
+        ]);
+        let mut diagnostics = Diagnostics::new();
+        let graph = Graph::from_github(
+            issues,
+            [],
+            &ParseOptions::default(),
+            &mut diagnostics,
+        );
+        let depends_on = |number| {
+            graph
+                .get(&issue_url(number))
+                .unwrap()
+                .depends_on_urls
+                .iter()
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        // The first copy's relation is dropped.
+        assert!(depends_on(2).is_empty());
+        assert_eq!(depends_on(3), [issue_url(1)]);
+        // The malformed reference is only reported once.
+        let codes: Vec<_> = diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.code)
+            .collect();
+        assert_eq!(codes, [Code::MalformedReference, Code::ConflictingItem]);
+    }
+
+    #[test]
+    fn test_repository_name() {
+        let graph = graph([
+            with(
+                issue(1, "OPEN", ""),
+                json!({"url": "https://github.com/acme/pullman/pull/1"}),
+            ),
+            with(
+                issue(2, "OPEN", ""),
+                json!({"url": "https://github.com/acme/issues-tracker/issues/2"}),
+            ),
+            with(
+                issue(3, "OPEN", ""),
+                json!({"url": "https://example.com/acme/pullman/3"}),
+            ),
+        ]);
+        let repository_names: Vec<_> = graph
+            .nodes
+            .values()
+            .map(|node| node.repository_name())
+            .collect();
+
+        assert_eq!(
+            repository_names,
+            [Some("acme/pullman"), Some("acme/issues-tracker"), None]
+        );
+    }
+
+    #[test]
+    fn test_find() {
+        let task = |id: &str, url: &str| Task {
+            id: id.to_owned(),
+            title: id.to_owned(),
+            state: GithubIssueState::Open,
+            url: Some(url.to_owned()),
+            depends_on: Vec::new(),
+            blocks: Vec::new(),
+        };
+        let options = ParseOptions::default();
+        let mut diagnostics = Diagnostics::new();
+        let mut builder = GraphBuilder::new(&options, &mut diagnostics);
+        for issue in github_issues([issue(2, "OPEN", "")]) {
+            builder.add_issue(issue);
+        }
+        builder.add_tasks([
+            task("two", "https://example.com/contracts/2"),
+            task("three", "https://example.com/contracts/3"),
+        ]);
+        let graph = builder.build();
+
+        assert_eq!(graph.find("#2").unwrap().url, issue_url(2));
+        assert_eq!(graph.find("foo/bar#2").unwrap().url, issue_url(2));
+        assert!(matches!(graph.find("#3"), Err(GraphError::NotFound { .. })));
+        assert_eq!(
+            graph.find("https://example.com/contracts/3").unwrap().text,
+            "three"
+        );
+    }
+}
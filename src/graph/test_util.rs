@@ -0,0 +1,65 @@
+//! Fixtures for tests that need a graph.
+
+use serde_json::{json, Value};
+
+use super::Graph;
+use crate::diagnostics::Diagnostics;
+use crate::github::GithubIssue;
+use crate::parse::ParseOptions;
+
+/// Returns issue foo/bar#`number`, titled "Issue `number`", as `gh issue list
+/// --json` prints it.  `state` is "OPEN" or "CLOSED".
+pub(crate) fn issue(number: u32, state: &str, body: &str) -> Value {
+    json!({
+        "body": body,
+        "closed": false,
+        "id": number.to_string(),
+        "labels": [],
+        "number": number,
+        "projectItems": [],
+        "state": state,
+        "title": format!("Issue {number}"),
+        "updatedAt": "2024-01-01T00:00:00Z",
+        "url": issue_url(number),
+    })
+}
+
+/// Returns the URL of issue foo/bar#`number`.
+pub(crate) fn issue_url(number: u32) -> String {
+    format!("https://github.com/foo/bar/issues/{number}")
+}
+
+/// Returns the issue with the fields added or replaced.
+pub(crate) fn with(mut issue: Value, fields: Value) -> Value {
+    let (Value::Object(issue_fields), Value::Object(fields)) =
+        (&mut issue, fields)
+    else {
+        panic!("expected objects");
+    };
+    issue_fields.extend(fields);
+    issue
+}
+
+/// Returns a label as `gh issue list --json` prints it.
+pub(crate) fn label(name: &str) -> Value {
+    json!({"id": name, "name": name, "description": "", "color": ""})
+}
+
+pub(crate) fn github_issues(
+    issues: impl IntoIterator<Item = Value>,
+) -> Vec<GithubIssue> {
+    issues
+        .into_iter()
+        .map(|issue| serde_json::from_value(issue).unwrap())
+        .collect()
+}
+
+/// Builds a graph of the issues with the default options.
+pub(crate) fn graph(issues: impl IntoIterator<Item = Value>) -> Graph {
+    Graph::from_github(
+        github_issues(issues),
+        [],
+        &ParseOptions::default(),
+        &mut Diagnostics::new(),
+    )
+}
@@ -0,0 +1,86 @@
+use crate::chart::ChartData;
+
+const TEMPLATE: &str = include_str!("assets/viewer.html");
+const STYLE: &str = include_str!("assets/viewer.css");
+const SCRIPT: &str = include_str!("assets/viewer.js");
+
+/// Renders chart data as a single HTML page that can be opened offline, with
+/// search, filters, and highlighting of what each item depends on.
+#[derive(Debug)]
+pub struct HtmlPage<'a> {
+    data: &'a ChartData,
+}
+
+impl<'a> HtmlPage<'a> {
+    pub fn new(data: &'a ChartData) -> Self {
+        Self { data }
+    }
+}
+
+impl std::fmt::Display for HtmlPage<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let title = if self.data.title.is_empty() {
+            "Dependencies"
+        } else {
+            self.data.title.as_str()
+        };
+        let json = serde_json::to_string(self.data)
+            .expect("chart data is serializable");
+
+        // Substitute placeholders in one pass so that values are never
+        // scanned for more placeholders.
+        let mut rest = TEMPLATE;
+        while let Some((before, after)) = rest.split_once("{{") {
+            let (name, after) = after
+                .split_once("}}")
+                .expect("template placeholders are closed");
+            f.write_str(before)?;
+            match name {
+                "title" => f.write_str(&html_escape(title))?,
+                "style" => f.write_str(STYLE)?,
+                "script" => f.write_str(SCRIPT)?,
+                "data" => f.write_str(&script_escape(&json))?,
+                _ => unreachable!("unknown template placeholder {name:?}"),
+            }
+            rest = after;
+        }
+        f.write_str(rest)
+    }
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Escapes JSON so that it can't end the script element it's embedded in.
+/// The result is still valid JSON.
+fn script_escape(json: &str) -> String {
+    json.replace('<', "\\u003c")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_page() {
+        let data = ChartData {
+            title: "<Plan> & more".to_owned(),
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        };
+        let html = HtmlPage::new(&data).to_string();
+
+        assert!(!html.contains("{{"));
+        assert!(html.contains("<title>&lt;Plan&gt; &amp; more</title>"));
+        assert!(html.contains(
+            r#"{"title":"\u003cPlan> & more","nodes":[],"edges":[]}</script>"#
+        ));
+        // No network fetches.
+        assert!(!html.contains("src="));
+        assert!(!html.contains("https://"));
+    }
+}
@@ -0,0 +1,297 @@
+use std::path::{Path, PathBuf};
+
+use serde::de::DeserializeOwned;
+
+use crate::diagnostics::{Code, Diagnostics};
+use crate::error::{Error, Result};
+
+/// Options for reading input files.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InputOptions {
+    /// Skip items that don't match the expected schema instead of failing.
+    pub skip_invalid: bool,
+}
+
+/// Reads and concatenates JSON arrays from files.  A file can also have
+/// several arrays in a row, as `gh api --paginate` outputs, an object with the
+/// array in `items`, as `gh project item-list` outputs, or one item per line
+/// (NDJSON).  Paths can be directories, where files ending in ".json" or
+/// ".ndjson" are read, or glob patterns, as [`expand_paths`] says.  A path of
+/// "-" reads from STDIN.  Items that are skipped because they're invalid are
+/// reported as diagnostics.
+pub fn read_json_lists<T: DeserializeOwned>(
+    paths: &[PathBuf],
+    options: InputOptions,
+    diagnostics: &mut Diagnostics,
+) -> Result<Vec<T>> {
+    let mut items = Vec::new();
+
+    for path in expand_paths(paths, &["json", "ndjson"])? {
+        let json = read_input(&path)?;
+        items.extend(parse_json_list(&path, &json, options, diagnostics)?);
+    }
+
+    Ok(items)
+}
+
+fn parse_json_list<T: DeserializeOwned>(
+    path: &Path,
+    json: &str,
+    options: InputOptions,
+    diagnostics: &mut Diagnostics,
+) -> Result<Vec<T>> {
+    // Most inputs are a single array of valid items, which is fastest to
+    // deserialize directly.
+    if let Ok(items) = serde_json::from_str(json) {
+        return Ok(items);
+    }
+
+    let values = json_items(json).map_err(|source| Error::Json {
+        path: path.to_owned(),
+        source,
+    })?;
+    let mut items = Vec::with_capacity(values.len());
+    for (index, value) in values.into_iter().enumerate() {
+        // Deserialize each item separately so that errors can say which item
+        // failed.
+        let url = value
+            .get("url")
+            .and_then(serde_json::Value::as_str)
+            .map(str::to_owned);
+        match serde_json::from_value::<T>(value) {
+            Ok(item) => items.push(item),
+            Err(source) => {
+                let error = Error::Record {
+                    path: path.to_owned(),
+                    index,
+                    url: url.clone(),
+                    source,
+                };
+                if !options.skip_invalid {
+                    return Err(error);
+                }
+                diagnostics.warn(
+                    Code::InvalidItem,
+                    format!("Skipped invalid item. {error}"),
+                    url.as_deref(),
+                );
+            }
+        }
+    }
+
+    Ok(items)
+}
+
+/// Replaces directories with the files in them that have one of the
+/// extensions, and glob patterns like `local/*_issues.json` with the files
+/// that match, each in sorted order.  Hidden files in directories are
+/// skipped.  Fails if a directory or pattern has no files, since that's
+/// likely a typo.
+pub fn expand_paths(
+    paths: &[PathBuf],
+    extensions: &[&str],
+) -> Result<Vec<PathBuf>> {
+    let mut expanded = Vec::new();
+
+    for path in paths {
+        let start = expanded.len();
+        if path.is_dir() {
+            let io_error = |source| Error::Io {
+                path: path.clone(),
+                source,
+            };
+            for entry in std::fs::read_dir(path).map_err(io_error)? {
+                let entry = entry.map_err(io_error)?;
+                let path = entry.path();
+                let hidden =
+                    entry.file_name().to_string_lossy().starts_with('.');
+                let included = path.extension().is_some_and(|extension| {
+                    extensions.iter().any(|included| {
+                        extension.eq_ignore_ascii_case(included)
+                    })
+                });
+                if !hidden && included && path.is_file() {
+                    expanded.push(path);
+                }
+            }
+        } else if let Some(pattern) =
+            path.to_str().filter(|path| path.contains(['*', '?', '[']))
+        {
+            let matches =
+                glob::glob(pattern).map_err(|source| Error::Pattern {
+                    pattern: pattern.to_owned(),
+                    source,
+                })?;
+            for entry in matches {
+                let entry = entry.map_err(|error| Error::Io {
+                    path: error.path().to_owned(),
+                    source: error.into(),
+                })?;
+                if entry.is_file() {
+                    expanded.push(entry);
+                }
+            }
+        } else {
+            // Includes "-" for STDIN.
+            expanded.push(path.clone());
+            continue;
+        }
+
+        if expanded.len() == start {
+            return Err(Error::NoFiles { path: path.clone() });
+        }
+        expanded[start..].sort();
+    }
+
+    Ok(expanded)
+}
+
+/// Returns the items in a sequence of JSON values, where arrays are
+/// flattened, e.g. one array per page, and other values like NDJSON lines are
+/// items.  A file with only an object with the array in `items` has those
+/// items.
+fn json_items(json: &str) -> serde_json::Result<Vec<serde_json::Value>> {
+    let mut values = serde_json::Deserializer::from_str(json)
+        .into_iter::<serde_json::Value>()
+        .collect::<serde_json::Result<Vec<_>>>()?;
+
+    if let [serde_json::Value::Object(object)] = values.as_mut_slice() {
+        if let Some(serde_json::Value::Array(items)) = object.get_mut("items") {
+            return Ok(std::mem::take(items));
+        }
+    }
+
+    let mut items = Vec::new();
+    for value in values {
+        match value {
+            serde_json::Value::Array(page) => items.extend(page),
+            value => items.push(value),
+        }
+    }
+    Ok(items)
+}
+
+/// Reads a file to a string.  A path of "-" reads from STDIN.
+pub fn read_input(path: &Path) -> Result<String> {
+    let result = if path == Path::new("-") {
+        // Read from STDIN.
+        let stdin = std::io::stdin().lock();
+        std::io::read_to_string(stdin)
+    } else {
+        // Read from a file.
+        std::fs::read_to_string(path)
+    };
+
+    result.map_err(|source| Error::Io {
+        path: path.to_owned(),
+        source,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Item {
+        number: u32,
+    }
+
+    #[test]
+    fn test_parse_json_list() {
+        let path = Path::new("issues.json");
+        let json = r#"[{"number": 1},
+                       {"number": "two", "url": "https://example.com/2"},
+                       {"number": 3}]"#;
+        let parse = |json, skip_invalid, diagnostics: &mut Diagnostics| {
+            let options = InputOptions { skip_invalid };
+            parse_json_list::<Item>(path, json, options, diagnostics)
+        };
+
+        let mut diagnostics = Diagnostics::new();
+        assert_eq!(
+            parse(r#"[{"number": 1}]"#, false, &mut diagnostics).unwrap(),
+            [Item { number: 1 }]
+        );
+
+        let error = parse(json, false, &mut diagnostics).unwrap_err();
+        let Error::Record {
+            path: error_path,
+            index,
+            url,
+            ..
+        } = &error
+        else {
+            panic!("expected a record error, got {error:?}");
+        };
+        assert_eq!(error_path, path);
+        assert_eq!(*index, 1);
+        assert_eq!(url.as_deref(), Some("https://example.com/2"));
+        assert!(
+            error
+                .to_string()
+                .starts_with("Failed to parse item 1 (https://example.com/2) in issues.json: "),
+            "{error}"
+        );
+        assert!(diagnostics.is_empty());
+
+        let items = parse(json, true, &mut diagnostics).unwrap();
+        assert_eq!(items, [Item { number: 1 }, Item { number: 3 }]);
+        let skipped: Vec<_> = diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.code, diagnostic.item.as_deref()))
+            .collect();
+        assert_eq!(
+            skipped,
+            [(Code::InvalidItem, Some("https://example.com/2"))]
+        );
+
+        let error = parse("[", true, &mut diagnostics).unwrap_err();
+        assert!(matches!(error, Error::Json { .. }), "{error:?}");
+    }
+
+    #[test]
+    fn test_expand_paths() {
+        let dir = std::env::temp_dir()
+            .join(format!("techtree-test-expand-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("nested.json")).unwrap();
+        for name in ["b.NDJSON", "a.json", "notes.txt", ".hidden.json"] {
+            std::fs::write(dir.join(name), "[]").unwrap();
+        }
+        let expand = |extensions: &[&str]| {
+            expand_paths(std::slice::from_ref(&dir), extensions)
+        };
+
+        let paths = expand(&["json", "ndjson"]);
+        let csv = expand(&["csv"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(paths.unwrap(), [dir.join("a.json"), dir.join("b.NDJSON")]);
+        assert!(matches!(csv, Err(Error::NoFiles { .. })), "{csv:?}");
+    }
+
+    #[test]
+    fn test_json_items() {
+        let numbers = |json: &str| {
+            json_items(json)
+                .unwrap()
+                .iter()
+                .map(|value| value["number"].as_u64().unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(numbers(r#"[{"number": 1}, {"number": 2}]"#), [1, 2]);
+        assert_eq!(numbers(r#"{"items": [{"number": 1}], "x": 0}"#), [1]);
+        assert_eq!(numbers("{\"number\": 1}\n{\"number\": 2}\n"), [1, 2]);
+        assert_eq!(numbers(r#"{"number": 1}"#), [1]);
+        assert_eq!(
+            numbers(r#"[{"number": 1}][{"number": 2}, {"number": 3}]"#),
+            [1, 2, 3]
+        );
+        assert_eq!(
+            numbers("[{\"number\": 1}]\n[]\n[{\"number\": 2}]\n"),
+            [1, 2]
+        );
+        assert!(numbers("").is_empty());
+        assert!(json_items("{\"number\": 1}\n{").is_err());
+    }
+}
@@ -0,0 +1,169 @@
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+use crate::input;
+
+/// An issue from a Jira CSV export.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JiraIssue {
+    /// The issue key, e.g. "PLAT-123".
+    pub key: String,
+    pub summary: String,
+    pub status: String,
+    /// "To Do", "In Progress", or "Done", if the export has it.
+    pub status_category: Option<String>,
+    /// The assignee's display name.
+    pub assignee: Option<String>,
+    pub labels: Vec<String>,
+    /// Keys of the issues that block this one, from "is blocked by" links.
+    pub blocked_by: Vec<String>,
+    /// Keys of the issues that this one blocks, from "blocks" links.
+    pub blocks: Vec<String>,
+}
+
+impl JiraIssue {
+    /// Returns true if the status category is "Done".  Older exports don't
+    /// have the category, so then the statuses "Done", "Closed", and
+    /// "Resolved" are done.
+    pub fn is_done(&self) -> bool {
+        match &self.status_category {
+            Some(category) => category.eq_ignore_ascii_case("done"),
+            None => ["done", "closed", "resolved"]
+                .iter()
+                .any(|status| self.status.eq_ignore_ascii_case(status)),
+        }
+    }
+}
+
+/// Reads and concatenates the issues in CSV files exported from Jira's issue
+/// search.  Paths can be directories, where files ending in ".csv" are read,
+/// or glob patterns, as [`input::expand_paths`] says.  A path of "-" reads
+/// from STDIN.
+pub fn read_jira_csv(paths: &[PathBuf]) -> Result<Vec<JiraIssue>> {
+    let mut issues = Vec::new();
+
+    for path in input::expand_paths(paths, &["csv"])? {
+        let text = input::read_input(&path)?;
+        issues.extend(parse_csv(&path, &text)?);
+    }
+
+    Ok(issues)
+}
+
+fn parse_csv(path: &Path, text: &str) -> Result<Vec<JiraIssue>> {
+    let csv_error = |source| Error::Csv {
+        path: path.to_owned(),
+        source,
+    };
+    // Excel adds a byte order mark.
+    let text = text.trim_start_matches('\u{feff}');
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(text.as_bytes());
+
+    // Columns with more than one value, like labels and links, are repeated
+    // with the same name.
+    let headers = reader.headers().map_err(csv_error)?.clone();
+    let columns = |name: &str| -> Vec<usize> {
+        headers
+            .iter()
+            .enumerate()
+            .filter(|(_, header)| header.trim().eq_ignore_ascii_case(name))
+            .map(|(index, _)| index)
+            .collect()
+    };
+    let required = |name: &str| {
+        columns(name)
+            .first()
+            .copied()
+            .ok_or_else(|| Error::MissingColumn {
+                path: path.to_owned(),
+                column: name.to_owned(),
+            })
+    };
+    let key = required("Issue key")?;
+    let summary = required("Summary")?;
+    let status = columns("Status");
+    let status_category = columns("Status Category");
+    let assignee = columns("Assignee");
+    let labels = columns("Labels");
+    let blocked_by = columns("Inward issue link (Blocks)");
+    let blocks = columns("Outward issue link (Blocks)");
+
+    let mut issues = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(csv_error)?;
+        let values = |columns: &[usize]| -> Vec<String> {
+            columns
+                .iter()
+                .filter_map(|&index| record.get(index))
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_owned)
+                .collect()
+        };
+        let value = |columns: &[usize]| values(columns).into_iter().next();
+
+        let key = record.get(key).unwrap_or_default().trim();
+        if key.is_empty() {
+            // E.g. a blank line at the end.
+            continue;
+        }
+        issues.push(JiraIssue {
+            key: key.to_owned(),
+            summary: record.get(summary).unwrap_or_default().to_owned(),
+            status: value(&status).unwrap_or_default(),
+            status_category: value(&status_category),
+            assignee: value(&assignee),
+            labels: values(&labels),
+            blocked_by: values(&blocked_by),
+            blocks: values(&blocks),
+        });
+    }
+
+    Ok(issues)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_csv() {
+        let csv = "\u{feff}Summary,Issue key,Issue id,Status,Assignee,Labels,Labels,Inward issue link (Blocks),Outward issue link (Blocks),Outward issue link (Blocks),Status Category
+Migrate the database,PLAT-1,10001,In Review,Ada Lovelace,db,infra,PLAT-3,PLAT-2,,In Progress
+\"Rotate keys, again\",PLAT-2,10002,Closed,,,,,,,Done
+";
+        let issues = parse_csv(Path::new("jira.csv"), csv).unwrap();
+        assert_eq!(
+            issues,
+            [
+                JiraIssue {
+                    key: "PLAT-1".to_owned(),
+                    summary: "Migrate the database".to_owned(),
+                    status: "In Review".to_owned(),
+                    status_category: Some("In Progress".to_owned()),
+                    assignee: Some("Ada Lovelace".to_owned()),
+                    labels: vec!["db".to_owned(), "infra".to_owned()],
+                    blocked_by: vec!["PLAT-3".to_owned()],
+                    blocks: vec!["PLAT-2".to_owned()],
+                },
+                JiraIssue {
+                    key: "PLAT-2".to_owned(),
+                    summary: "Rotate keys, again".to_owned(),
+                    status: "Closed".to_owned(),
+                    status_category: Some("Done".to_owned()),
+                    ..JiraIssue::default()
+                },
+            ]
+        );
+        assert!(!issues[0].is_done());
+        assert!(issues[1].is_done());
+
+        let error = parse_csv(Path::new("jira.csv"), "Summary\nOne\n");
+        assert!(
+            matches!(error, Err(Error::MissingColumn { ref column, .. }) if column == "Issue key"),
+            "{error:?}"
+        );
+    }
+}
@@ -0,0 +1,29 @@
+//! Dependency analysis of GitHub issues, used to draw a map, or tech tree, of
+//! tasks.
+//!
+//! Parse issues from `gh issue list --json` output with the [`github`] model,
+//! build a [`graph::Graph`], and render it with a renderer like
+//! [`chart::Flowchart`].
+
+pub mod chart;
+pub mod clock;
+pub mod config;
+pub mod diagnostics;
+pub mod diff;
+pub mod digest;
+mod error;
+pub mod forecast;
+pub mod gantt;
+pub mod github;
+pub mod graph;
+pub mod html;
+pub mod input;
+pub mod jira;
+pub mod order;
+pub mod parse;
+pub mod task;
+pub mod tree;
+pub mod url;
+mod util;
+
+pub use error::{Error, GraphError, Result};
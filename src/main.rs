@@ -1,33 +1,116 @@
-use std::path::{Path, PathBuf};
+use std::io::IsTerminal;
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
 use std::process::ExitCode;
 
-use clap::{Args, Parser};
-use indexmap::{IndexMap, IndexSet};
+use clap::{Args, Parser, ValueEnum};
+use techtree::chart::{Filter, Flowchart, GroupBy, Limits};
+use techtree::clock::{self, Clock, FixedClock, SystemClock};
+use techtree::config::Config;
+use techtree::diagnostics::Diagnostics;
+use techtree::diff::{DiffFlowchart, GraphDiff};
+use techtree::digest::Digest;
+use techtree::forecast::{Estimates, Forecast, ForecastOptions};
+use techtree::github::{GithubIssue, GithubProjectItem, GithubPullRequest};
+use techtree::graph::{Graph, GraphBuilder, NodeKind};
+use techtree::html::HtmlPage;
+use techtree::input::{self, InputOptions};
+use techtree::jira;
+use techtree::order::WorkOrder;
+use techtree::parse::ParseOptions;
+use techtree::task;
+use techtree::tree::{Direction, Tree};
 use time::OffsetDateTime;
 
-use crate::chart::{Flowchart, Node, NodeId};
-use crate::github::GithubIssue;
-
-mod chart;
-mod github;
-mod parse;
-mod util;
-
-type AppResult<T> = Result<T, Box<dyn std::error::Error>>;
+type AppResult<T> = techtree::Result<T>;
 
 const DEFAULT_PRIOR_DAYS: u16 = 7;
 
 #[derive(Parser)]
 #[command(version, about = "GitHub Projects dependency analysis")]
 struct Cli {
+    #[command(flatten)]
+    global: GlobalArgs,
     #[clap(subcommand)]
     command: Commands,
 }
 
+/// Options for all commands.
+#[derive(Debug, Args)]
+struct GlobalArgs {
+    #[arg(
+        long,
+        global = true,
+        help = "Config file.  Default is techtree.toml in the current directory, if it exists."
+    )]
+    config: Option<PathBuf>,
+    #[arg(
+        long,
+        global = true,
+        help = "Exit with failure without output if there are any warnings"
+    )]
+    warnings_as_errors: bool,
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t = DiagnosticsFormat::Text,
+        help = "Format of warnings written to STDERR"
+    )]
+    diagnostics_format: DiagnosticsFormat,
+    #[arg(
+        long,
+        global = true,
+        value_name = "DATE",
+        value_parser = clock::parse_date,
+        help = "Use this RFC 3339 date time instead of now, for reproducible output, e.g. 2024-01-31T00:00:00Z"
+    )]
+    as_of: Option<OffsetDateTime>,
+}
+
+impl GlobalArgs {
+    /// Returns the clock to use for "now".
+    fn clock(&self) -> Box<dyn Clock> {
+        match self.as_of {
+            Some(as_of) => Box::new(FixedClock(as_of)),
+            None => Box::new(SystemClock),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum DiagnosticsFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// Markdown with a Mermaid flowchart.
+    #[default]
+    Mermaid,
+    /// The flowchart's nodes and edges as JSON.
+    Json,
+    /// A standalone, interactive HTML page.
+    Html,
+    /// Markdown with a Mermaid gantt chart of the issues.
+    Gantt,
+}
+
 #[derive(Parser)]
 enum Commands {
     #[command(about = "Visualize dependency map")]
     Map(MapArgs),
+    #[command(about = "Compare two snapshots of issues")]
+    Diff(DiffArgs),
+    #[command(about = "List issues that were recently unblocked")]
+    Digest(DigestArgs),
+    #[command(about = "Show what an issue depends on as a tree")]
+    Tree(TreeArgs),
+    #[command(about = "List open issues in the order they can be worked on")]
+    Order(OrderArgs),
+    #[command(about = "Project when goals will be done from estimates")]
+    Forecast(ForecastArgs),
 }
 
 #[derive(Debug, Args)]
@@ -36,167 +119,734 @@ struct MapArgs {
     pub header: Option<String>,
     #[arg(long, help = "Mermaid diagram title")]
     pub title: Option<String>,
-    #[arg(long, short, help = "Output all tasks; don't use default filter")]
+    #[arg(
+        long,
+        short,
+        overrides_with = "no_all",
+        help = "Output all tasks; don't use default filter"
+    )]
     pub all: bool,
     #[arg(
         long,
-        help = "JSON Issues List stored in a file.  You can use this multiple times."
+        overrides_with = "all",
+        help = "Use the default filter, even if the config file sets `all`"
+    )]
+    pub no_all: bool,
+    #[arg(
+        long,
+        help = "JSON Issues List stored in a file, directory, or glob pattern like `local/*_issues.json`.  You can use this multiple times."
     )]
     pub issues: Option<Vec<PathBuf>>,
+    #[arg(
+        long,
+        help = "JSON Pull Requests List stored in a file, directory, or glob pattern, from `gh pr list`.  You can use this multiple times."
+    )]
+    pub prs: Option<Vec<PathBuf>>,
+    #[command(flatten)]
+    pub extra: ExtraInputArgs,
+    #[command(flatten)]
+    pub parse: ParseArgs,
+    #[arg(
+        long,
+        value_name = "DATE",
+        value_parser = clock::parse_date,
+        conflicts_with = "as_of",
+        help = "Show the map as it was at a past RFC 3339 date time, using when issues were created and closed"
+    )]
+    pub at: Option<OffsetDateTime>,
     #[arg(long, help = "Filter to only include given project title")]
     pub include_project: Option<String>,
+    #[arg(
+        long,
+        value_name = "none|repository|project|milestone",
+        help = "Group nodes into subgraphs"
+    )]
+    pub group_by: Option<GroupBy>,
     #[arg(
         long,
         help = "Additionally include closed issues that were updated in the last N days.  Default is 7 days."
     )]
     pub prior_days: Option<u16>,
+    #[arg(
+        long,
+        overrides_with = "no_reduce",
+        help = "Don't draw dependencies that are implied by others, e.g. A -> C when A -> B -> C"
+    )]
+    pub reduce: bool,
+    #[arg(
+        long,
+        overrides_with = "reduce",
+        help = "Draw every dependency, even if the config file sets `reduce`"
+    )]
+    pub no_reduce: bool,
+    #[arg(long, value_enum, default_value_t, help = "Output format")]
+    pub format: OutputFormat,
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Split the Mermaid output into diagrams with at most N nodes each, so that GitHub renders them"
+    )]
+    pub max_nodes: Option<usize>,
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Split the Mermaid output into diagrams with at most N edges each"
+    )]
+    pub max_edges: Option<usize>,
+}
+
+#[derive(Debug, Args)]
+struct DiffArgs {
+    #[arg(
+        long,
+        required = true,
+        help = "JSON Issues List of the older snapshot.  You can use this multiple times."
+    )]
+    pub before: Vec<PathBuf>,
+    #[arg(
+        long,
+        required = true,
+        help = "JSON Issues List of the newer snapshot.  You can use this multiple times."
+    )]
+    pub after: Vec<PathBuf>,
+    #[command(flatten)]
+    pub parse: ParseArgs,
+    #[arg(long, help = "Also output a Mermaid diagram of the changes")]
+    pub mermaid: bool,
+}
+
+#[derive(Debug, Args)]
+struct DigestArgs {
+    #[arg(
+        long,
+        help = "JSON Issues List stored in a file, directory, or glob pattern like `local/*_issues.json`.  You can use this multiple times."
+    )]
+    pub issues: Option<Vec<PathBuf>>,
+    #[command(flatten)]
+    pub extra: ExtraInputArgs,
+    #[command(flatten)]
+    pub parse: ParseArgs,
+    #[arg(
+        long,
+        help = "Include issues whose last prerequisite closed in the last N days.  Default is 7 days."
+    )]
+    pub days: Option<u16>,
+}
+
+#[derive(Debug, Args)]
+struct TreeArgs {
+    #[arg(help = "The issue's URL, owner/repo#123, or #123")]
+    pub issue: String,
+    #[arg(
+        long,
+        help = "JSON Issues List stored in a file, directory, or glob pattern like `local/*_issues.json`.  You can use this multiple times."
+    )]
+    pub issues: Option<Vec<PathBuf>>,
+    #[command(flatten)]
+    pub extra: ExtraInputArgs,
+    #[command(flatten)]
+    pub parse: ParseArgs,
+    #[arg(long, help = "Show what depends on the issue instead")]
+    pub reverse: bool,
+}
+
+#[derive(Debug, Args)]
+struct OrderArgs {
+    #[arg(
+        long,
+        help = "JSON Issues List stored in a file, directory, or glob pattern like `local/*_issues.json`.  You can use this multiple times."
+    )]
+    pub issues: Option<Vec<PathBuf>>,
+    #[command(flatten)]
+    pub extra: ExtraInputArgs,
+    #[command(flatten)]
+    pub parse: ParseArgs,
+}
+
+#[derive(Debug, Args)]
+struct ForecastArgs {
+    #[arg(
+        long,
+        help = "JSON Issues List stored in a file, directory, or glob pattern like `local/*_issues.json`.  You can use this multiple times."
+    )]
+    pub issues: Option<Vec<PathBuf>>,
+    #[command(flatten)]
+    pub extra: ExtraInputArgs,
+    #[command(flatten)]
+    pub parse: ParseArgs,
+    #[arg(
+        long,
+        value_name = "N",
+        default_value = "1",
+        help = "How many issues can be worked on at once"
+    )]
+    pub team_size: NonZeroUsize,
+    #[arg(
+        long,
+        value_name = "FILE",
+        help = "TOML file of estimates in days, e.g. \"owner/repo#123\" = 3.  Otherwise labels like \"size: 3\" are used."
+    )]
+    pub estimates: Option<PathBuf>,
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Custom Project number field with estimates in days, e.g. Size"
+    )]
+    pub estimate_field: Option<String>,
+    #[arg(
+        long,
+        value_name = "DAYS",
+        default_value_t = 1.0,
+        help = "Estimate for issues without one"
+    )]
+    pub default_estimate: f64,
+}
+
+/// Inputs besides the issue lists, merged with the issues.
+#[derive(Debug, Args)]
+struct ExtraInputArgs {
+    #[arg(
+        long,
+        help = "JSON from `gh project item-list --format json` stored in a file, merged with the issues.  Draft issues are included.  You can use this multiple times."
+    )]
+    pub project_items: Option<Vec<PathBuf>>,
+    #[arg(
+        long,
+        value_name = "TITLE",
+        help = "The title of the project that --project-items are from"
+    )]
+    pub project_title: Option<String>,
+    #[arg(
+        long,
+        help = "YAML or TOML file of tasks that aren't GitHub issues, like vendor contracts.  TOML files must end in .toml.  You can use this multiple times."
+    )]
+    pub tasks: Option<Vec<PathBuf>>,
+    #[arg(
+        long,
+        help = "CSV exported from a Jira issue search, with issue links.  Set --jira-url to link to the issues.  You can use this multiple times."
+    )]
+    pub jira_csv: Option<Vec<PathBuf>>,
+}
+
+impl ExtraInputArgs {
+    /// Returns the inputs, or the config file's when they aren't given.
+    fn inputs<'a>(&'a self, config: &'a Config) -> ExtraInputs<'a> {
+        ExtraInputs {
+            project_items: self
+                .project_items
+                .as_deref()
+                .unwrap_or(&config.project_items),
+            project_title: self
+                .project_title
+                .as_deref()
+                .or(config.project_title.as_deref()),
+            tasks: self.tasks.as_deref().unwrap_or(&config.tasks),
+            jira_csv: self.jira_csv.as_deref().unwrap_or(&config.jira_csv),
+        }
+    }
+}
+
+/// Inputs besides the issue lists.  See [`ExtraInputArgs`].
+#[derive(Debug, Clone, Copy, Default)]
+struct ExtraInputs<'a> {
+    project_items: &'a [PathBuf],
+    /// The title of the project that `project_items` are from.
+    project_title: Option<&'a str>,
+    tasks: &'a [PathBuf],
+    jira_csv: &'a [PathBuf],
+}
+
+/// Options for reading and parsing inputs, shared by commands.
+#[derive(Debug, Args)]
+struct ParseArgs {
+    #[arg(
+        long = "github-host",
+        value_name = "HOST",
+        help = "Additional GitHub host to recognize links to, e.g. a GitHub Enterprise Server host.  You can use this multiple times."
+    )]
+    pub github_hosts: Vec<String>,
+    #[arg(
+        long,
+        value_name = "URL",
+        help = "Base URL of a Jira site, e.g. https://example.atlassian.net, to recognize Jira keys like PLAT-123 in issues and link to them"
+    )]
+    pub jira_url: Option<String>,
+    #[arg(
+        long,
+        help = "Skip items in input files that can't be parsed instead of failing"
+    )]
+    pub skip_invalid: bool,
+}
+
+impl ParseArgs {
+    fn parse_options(&self, config: &Config) -> AppResult<ParseOptions> {
+        // Command line arguments override the config file.
+        let github_hosts = if self.github_hosts.is_empty() {
+            &config.github_hosts
+        } else {
+            &self.github_hosts
+        };
+
+        let mut options = ParseOptions::new(
+            github_hosts.iter().map(|host| host_name(host)).collect(),
+            &config.keywords.depends_on,
+        )?;
+        options.jira_url = self.jira_url.clone().or(config.jira_url.clone());
+        Ok(options)
+    }
+
+    fn input_options(&self) -> InputOptions {
+        InputOptions {
+            skip_invalid: self.skip_invalid,
+        }
+    }
 }
 
 fn main() -> ExitCode {
-    let result = try_main();
-    match result {
+    let cli = Cli::parse();
+    let diagnostics_format = cli.global.diagnostics_format;
+    match try_main(cli) {
         Ok(code) => code,
         Err(error) => {
-            eprintln!("{error}");
+            report_failure(diagnostics_format, &error.to_string());
             ExitCode::FAILURE
         }
     }
 }
 
-fn try_main() -> AppResult<ExitCode> {
-    let cli = Cli::parse();
+fn try_main(cli: Cli) -> AppResult<ExitCode> {
+    let config = Config::discover(cli.global.config.as_deref())?;
 
     match cli.command {
         Commands::Map(args) => {
-            print_dependencies_map(args)?;
+            print_dependencies_map(&cli.global, args, config)
         }
+        Commands::Diff(args) => print_diff(&cli.global, &args, &config),
+        Commands::Digest(args) => print_digest(&cli.global, args, config),
+        Commands::Tree(args) => print_tree(&cli.global, args, config),
+        Commands::Order(args) => print_order(&cli.global, args, config),
+        Commands::Forecast(args) => print_forecast(&cli.global, args, config),
     }
+}
 
-    Ok(ExitCode::SUCCESS)
+/// Returns the value of a flag that has a `--no-` form, or `None` if neither
+/// was given, so that the config file's value is used.
+fn flag(on: bool, off: bool) -> Option<bool> {
+    match (on, off) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        (false, false) => None,
+    }
 }
 
-fn print_dependencies_map(args: MapArgs) -> AppResult<()> {
-    let include_project_only = args.include_project;
-
-    let issues: Vec<GithubIssue> = args
-        .issues
-        .unwrap_or_default()
-        .iter()
-        .map(|path| {
-            let issues_json_result = if path == Path::new("-") {
-                // Read from STDIN.
-                let stdin = std::io::stdin().lock();
-                std::io::read_to_string(stdin)
-            } else {
-                // Read from a file.
-                std::fs::read_to_string(path)
-            };
-            let issues_json = match issues_json_result {
-                Ok(i) => i,
-                Err(error) => {
-                    let boxed: Box<dyn std::error::Error> = Box::new(error);
-                    return Err(boxed);
-                }
-            };
-            // Note: It's faster to read the entire file and then parse it.
-            // https://github.com/serde-rs/json/issues/160
-            serde_json::from_str::<Vec<GithubIssue>>(&issues_json)
-                .map_err(|err| Box::new(err).into())
-        })
-        .collect::<Result<Vec<_>, _>>()?
-        .into_iter()
-        .flatten()
-        .collect();
+/// Writes diagnostics to STDERR.  Returns false if output should be
+/// suppressed because warnings are treated as errors.
+fn report_diagnostics(global: &GlobalArgs, diagnostics: &Diagnostics) -> bool {
+    match global.diagnostics_format {
+        DiagnosticsFormat::Text => {
+            for diagnostic in diagnostics {
+                eprintln!("{diagnostic}");
+            }
+        }
+        DiagnosticsFormat::Json => {
+            let json = serde_json::to_string(diagnostics)
+                .expect("diagnostics are serializable");
+            eprintln!("{json}");
+        }
+    }
 
-    // Only show closed nodes that have been recently updated.
-    let updated_after = OffsetDateTime::now_utc()
-        - time::Duration::days(i64::from(
-            args.prior_days.unwrap_or(DEFAULT_PRIOR_DAYS),
-        ));
+    if global.warnings_as_errors && !diagnostics.is_empty() {
+        report_failure(
+            global.diagnostics_format,
+            &format!("{} warning(s) treated as errors", diagnostics.len()),
+        );
+        return false;
+    }
+    true
+}
 
-    let mut flowchart = Flowchart::new(
-        args.title.unwrap_or_default(),
-        args.all,
-        include_project_only,
-        Some(updated_after),
-    );
+/// Writes the reason the command failed to STDERR, as an object with an
+/// "error" message in JSON.
+fn report_failure(format: DiagnosticsFormat, message: &str) {
+    match format {
+        DiagnosticsFormat::Text => eprintln!("error: {message}"),
+        DiagnosticsFormat::Json => {
+            eprintln!("{}", serde_json::json!({ "error": message }));
+        }
+    }
+}
 
-    let mut blocks: IndexMap<NodeId, u32> = IndexMap::default();
+fn print_dependencies_map(
+    global: &GlobalArgs,
+    args: MapArgs,
+    config: Config,
+) -> AppResult<ExitCode> {
+    let parse_options = args.parse.parse_options(&config)?;
 
-    let mut id = 1_usize;
+    let mut diagnostics = Diagnostics::new();
+    let graph = load_graph(
+        args.issues.as_deref().unwrap_or(&config.issues),
+        args.prs.as_deref().unwrap_or(&config.prs),
+        args.extra.inputs(&config),
+        &parse_options,
+        args.parse.input_options(),
+        &mut diagnostics,
+    )?;
 
-    for issue in issues {
-        // Use a set to dedupe the dependencies.
-        let mut depends_on_urls = IndexSet::new();
-
-        if let Some(repository) = issue.repository() {
-            // Parse dependencies from the body text.
-            let dependencies = parse::relations(
-                issue.body.as_str(),
-                repository,
-                issue.title.as_str(),
-            )
-            .map(|relation| relation.target.into_owned());
-
-            depends_on_urls.extend(dependencies);
-
-            // Increment the count of all the things that block this item.
-            for depends_on_url in &depends_on_urls {
-                let previous_count =
-                    blocks.entry(depends_on_url.clone()).or_default();
-                *previous_count = previous_count.saturating_add(1);
-            }
-        } else {
-            eprintln!("Warning: Unexpected issue URL; couldn't parse repository: {:?}", issue.url);
-        }
+    // Command line arguments override the config file.
+    let include_project_only = args.include_project.or(config.include_project);
+    let header = args.header.or(config.header);
+    let title = args.title.or(config.title);
+    let prior_days = args.prior_days.or(config.prior_days);
 
-        let project_titles = issue
-            .project_items
-            .iter()
-            .map(|item| item.title.clone())
-            .collect();
-
-        let node = Node {
-            id: id.to_string(),
-            text: issue.title,
-            url: issue.url,
-            state: issue.state,
-            labels: issue
-                .labels
-                .iter()
-                .map(|label| label.name.clone())
-                .collect(),
-            project_titles,
-            depends_on_urls,
-            blocks_count: 0,
-            updated_at: issue.updated_at,
-        };
-        flowchart.nodes.insert(node.url.clone(), node);
+    let graph = match args.at {
+        Some(at) => graph.at(at, &mut diagnostics),
+        None => graph,
+    };
+    let has_pull_requests = graph
+        .nodes
+        .values()
+        .any(|node| node.kind == NodeKind::PullRequest);
 
-        id = id.checked_add(1).expect("Overflowed number of items");
+    if !report_diagnostics(global, &diagnostics) {
+        return Ok(ExitCode::FAILURE);
     }
 
-    // Update nodes to have the count of items they block.
-    for (url, count) in blocks {
-        let Some(blocking_node) = flowchart.nodes.get_mut(&url) else {
-            continue;
-        };
-        blocking_node.blocks_count = count;
+    // Only show closed nodes that have been recently updated.
+    let filter = Filter {
+        include_project_only,
+        closed_within: Some(time::Duration::days(i64::from(
+            prior_days.unwrap_or(DEFAULT_PRIOR_DAYS),
+        ))),
+        clock: match args.at {
+            Some(at) => Box::new(FixedClock(at)),
+            None => global.clock(),
+        },
+    };
+
+    let mut flowchart = Flowchart::new(
+        &graph,
+        title.unwrap_or_default(),
+        flag(args.all, args.no_all).unwrap_or(config.all),
+        filter,
+    );
+    flowchart.group_by = args.group_by.or(config.group_by).unwrap_or_default();
+    flowchart.style = config.style;
+    flowchart.reduce =
+        flag(args.reduce, args.no_reduce).unwrap_or(config.reduce);
+    flowchart.limits = Limits {
+        max_nodes: args.max_nodes.or(config.max_nodes),
+        max_edges: args.max_edges.or(config.max_edges),
+    };
+
+    match args.format {
+        OutputFormat::Mermaid => {}
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(&flowchart.data())
+                .expect("chart data is serializable");
+            println!("{json}");
+            return Ok(ExitCode::SUCCESS);
+        }
+        OutputFormat::Html => {
+            print!("{}", HtmlPage::new(&flowchart.data()));
+            return Ok(ExitCode::SUCCESS);
+        }
+        OutputFormat::Gantt => {
+            let gantt = flowchart.gantt()?;
+            if let Some(header) = header {
+                println!("{header}");
+                println!();
+            }
+            println!("```mermaid");
+            print!("{gantt}");
+            println!("```");
+            return Ok(ExitCode::SUCCESS);
+        }
     }
 
     // Print markdown.
-    if let Some(header) = args.header {
+    if let Some(header) = header {
         println!("{header}");
         println!();
     }
     // spell-checker: disable-next-line
     println!("A &rarr; B means A blocks B, or B depends on A.");
+    if has_pull_requests {
+        println!("Hexagons are pull requests, with a dotted arrow to each issue they close.");
+    }
+    let split = flowchart.split();
+    if split.len() > 1 {
+        println!("The map is split into {} diagrams.  Nodes with a dashed border are in another diagram.", split.len());
+    }
     // spell-checker: disable-next-line
     println!("Press &harr; for full screen.");
-    println!();
-    println!("```mermaid");
-    println!("{flowchart}");
-    println!("```");
+    for part in split.parts() {
+        println!();
+        println!("```mermaid");
+        println!("{part}");
+        println!("```");
+    }
 
-    Ok(())
+    Ok(ExitCode::SUCCESS)
+}
+
+fn print_diff(
+    global: &GlobalArgs,
+    args: &DiffArgs,
+    config: &Config,
+) -> AppResult<ExitCode> {
+    let parse_options = args.parse.parse_options(config)?;
+
+    let mut diagnostics = Diagnostics::new();
+    let before = load_graph(
+        &args.before,
+        &[],
+        ExtraInputs::default(),
+        &parse_options,
+        args.parse.input_options(),
+        &mut diagnostics,
+    )?;
+    let after = load_graph(
+        &args.after,
+        &[],
+        ExtraInputs::default(),
+        &parse_options,
+        args.parse.input_options(),
+        &mut diagnostics,
+    )?;
+
+    if !report_diagnostics(global, &diagnostics) {
+        return Ok(ExitCode::FAILURE);
+    }
+
+    let diff = GraphDiff::new(&before, &after);
+    print!("{diff}");
+    if args.mermaid && !diff.is_empty() {
+        println!("```mermaid");
+        println!("{}", DiffFlowchart::new(&diff, &after));
+        println!("```");
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn print_digest(
+    global: &GlobalArgs,
+    args: DigestArgs,
+    config: Config,
+) -> AppResult<ExitCode> {
+    let parse_options = args.parse.parse_options(&config)?;
+
+    let mut diagnostics = Diagnostics::new();
+    let graph = load_graph(
+        args.issues.as_deref().unwrap_or(&config.issues),
+        &[],
+        args.extra.inputs(&config),
+        &parse_options,
+        args.parse.input_options(),
+        &mut diagnostics,
+    )?;
+
+    if !report_diagnostics(global, &diagnostics) {
+        return Ok(ExitCode::FAILURE);
+    }
+
+    let until = global.clock().now();
+    let days = args.days.unwrap_or(DEFAULT_PRIOR_DAYS);
+    let since = until - time::Duration::days(i64::from(days));
+    print!("{}", Digest::new(&graph, since, until));
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn print_tree(
+    global: &GlobalArgs,
+    args: TreeArgs,
+    config: Config,
+) -> AppResult<ExitCode> {
+    let parse_options = args.parse.parse_options(&config)?;
+
+    let mut diagnostics = Diagnostics::new();
+    let graph = load_graph(
+        args.issues.as_deref().unwrap_or(&config.issues),
+        &[],
+        args.extra.inputs(&config),
+        &parse_options,
+        args.parse.input_options(),
+        &mut diagnostics,
+    )?;
+
+    if !report_diagnostics(global, &diagnostics) {
+        return Ok(ExitCode::FAILURE);
+    }
+
+    let root = graph.find(&args.issue)?;
+    let direction = if args.reverse {
+        Direction::Dependents
+    } else {
+        Direction::Prerequisites
+    };
+    let mut tree = Tree::new(&graph, root, direction);
+    // See <https://no-color.org>.
+    tree.color = std::io::stdout().is_terminal()
+        && std::env::var_os("NO_COLOR").is_none();
+    print!("{tree}");
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn print_order(
+    global: &GlobalArgs,
+    args: OrderArgs,
+    config: Config,
+) -> AppResult<ExitCode> {
+    let parse_options = args.parse.parse_options(&config)?;
+
+    let mut diagnostics = Diagnostics::new();
+    let graph = load_graph(
+        args.issues.as_deref().unwrap_or(&config.issues),
+        &[],
+        args.extra.inputs(&config),
+        &parse_options,
+        args.parse.input_options(),
+        &mut diagnostics,
+    )?;
+
+    if !report_diagnostics(global, &diagnostics) {
+        return Ok(ExitCode::FAILURE);
+    }
+
+    print!("{}", WorkOrder::new(&graph)?);
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn print_forecast(
+    global: &GlobalArgs,
+    args: ForecastArgs,
+    config: Config,
+) -> AppResult<ExitCode> {
+    let parse_options = args.parse.parse_options(&config)?;
+
+    let mut diagnostics = Diagnostics::new();
+    let graph = load_graph(
+        args.issues.as_deref().unwrap_or(&config.issues),
+        &[],
+        args.extra.inputs(&config),
+        &parse_options,
+        args.parse.input_options(),
+        &mut diagnostics,
+    )?;
+    let mut estimates = Estimates::new(args.default_estimate);
+    estimates.field = args.estimate_field;
+    if let Some(path) = &args.estimates {
+        estimates.load(path, &graph, &mut diagnostics)?;
+    }
+
+    if !report_diagnostics(global, &diagnostics) {
+        return Ok(ExitCode::FAILURE);
+    }
+
+    let options = ForecastOptions {
+        team_size: args.team_size,
+        start: global.clock().now(),
+    };
+    print!("{}", Forecast::new(&graph, &estimates, options)?);
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Reads issues and pull requests from files and builds a graph.
+fn load_graph(
+    issues: &[PathBuf],
+    pull_requests: &[PathBuf],
+    extra: ExtraInputs<'_>,
+    parse_options: &ParseOptions,
+    input_options: InputOptions,
+    diagnostics: &mut Diagnostics,
+) -> AppResult<Graph> {
+    let issues = input::read_json_lists::<GithubIssue>(
+        issues,
+        input_options,
+        diagnostics,
+    )?;
+    let pull_requests = input::read_json_lists::<GithubPullRequest>(
+        pull_requests,
+        input_options,
+        diagnostics,
+    )?;
+    let project_items = input::read_json_lists::<GithubProjectItem>(
+        extra.project_items,
+        input_options,
+        diagnostics,
+    )?;
+    let tasks = task::read_tasks(extra.tasks)?;
+    let jira_issues = jira::read_jira_csv(extra.jira_csv)?;
+
+    // Add project items and tasks last so that they're merged into the
+    // issues.
+    let mut builder = GraphBuilder::new(parse_options, diagnostics);
+    for issue in issues {
+        builder.add_issue(issue);
+    }
+    for pull_request in pull_requests {
+        builder.add_pull_request(pull_request);
+    }
+    for issue in jira_issues {
+        builder.add_jira_issue(issue);
+    }
+    for item in project_items {
+        builder.add_project_item(item, extra.project_title);
+    }
+    builder.add_tasks(tasks);
+    Ok(builder.build())
+}
+
+/// Returns the host name from a host given by the user, which may include a
+/// scheme or trailing slash, e.g. "https://github.example.com/".
+fn host_name(host: &str) -> String {
+    let host = host.trim();
+    let host = host
+        .split_once("://")
+        .map_or(host, |(_, host)| host)
+        .trim_end_matches('/');
+    host.to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    #[test]
+    fn test_cli() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_flags_override_config() {
+        // (arguments, value in the config file, expected value)
+        let cases = [
+            (&[][..], false, false),
+            (&[], true, true),
+            (&["--reduce"], false, true),
+            (&["--no-reduce"], true, false),
+            (&["--reduce", "--no-reduce"], true, false),
+            (&["--no-reduce", "--reduce"], false, true),
+        ];
+        for (args, config, expected) in cases {
+            let cli =
+                Cli::try_parse_from(["techtree", "map"].iter().chain(args))
+                    .unwrap();
+            let Commands::Map(args) = cli.command else {
+                unreachable!();
+            };
+            assert_eq!(
+                flag(args.reduce, args.no_reduce).unwrap_or(config),
+                expected,
+                "{args:?}"
+            );
+        }
+    }
 }
@@ -0,0 +1,271 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use indexmap::IndexSet;
+
+use crate::error::GraphError;
+use crate::graph::{Graph, Node, NodeKind};
+use crate::util::{markdown_link, regex};
+
+/// Open issues in the order they can be worked on.  Issues in the first wave
+/// can start now, and issues in each later wave can start once the waves
+/// before it are done.
+#[derive(Debug)]
+pub struct WorkOrder<'g> {
+    pub waves: Vec<Vec<&'g Node>>,
+}
+
+impl<'g> WorkOrder<'g> {
+    /// Sorts open issues by how deep they are behind other open issues.
+    /// Within a wave, issues with a higher priority label come first, then
+    /// issues that block more.  Fails if there are dependency cycles, since
+    /// those can never start.
+    pub fn new(graph: &'g Graph) -> Result<Self, GraphError> {
+        Self::with_filter(graph, |_| true)
+    }
+
+    /// Like [`WorkOrder::new`], but only orders the open issues that
+    /// `include` returns true for, e.g. the ones that are drawn.
+    /// Dependencies on other issues are ignored.
+    pub fn with_filter(
+        graph: &'g Graph,
+        include: impl Fn(&Node) -> bool,
+    ) -> Result<Self, GraphError> {
+        let nodes: Vec<&Node> = graph
+            .nodes
+            .values()
+            .filter(|node| {
+                node.kind == NodeKind::Issue && node.is_open() && include(node)
+            })
+            .collect();
+        let indexes: HashMap<&str, usize> = nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| (node.url.as_str(), index))
+            .collect();
+        // Open prerequisites of each node, by index.
+        let prerequisites: Vec<IndexSet<usize>> = nodes
+            .iter()
+            .map(|node| {
+                graph
+                    .dependencies(node)
+                    .filter_map(|prerequisite| {
+                        indexes.get(prerequisite.url.as_str()).copied()
+                    })
+                    .collect()
+            })
+            .collect();
+        let mut dependents = vec![Vec::new(); nodes.len()];
+        for (index, node_prerequisites) in prerequisites.iter().enumerate() {
+            for &prerequisite in node_prerequisites {
+                dependents[prerequisite].push(index);
+            }
+        }
+
+        // Kahn's algorithm, one wave at a time.
+        let mut remaining: Vec<usize> =
+            prerequisites.iter().map(IndexSet::len).collect();
+        let mut wave: Vec<usize> = (0..nodes.len())
+            .filter(|&index| remaining[index] == 0)
+            .collect();
+        let mut waves = Vec::new();
+        let mut done = 0;
+        while !wave.is_empty() {
+            done += wave.len();
+            let mut next = Vec::new();
+            for &index in &wave {
+                for &dependent in &dependents[index] {
+                    remaining[dependent] -= 1;
+                    if remaining[dependent] == 0 {
+                        next.push(dependent);
+                    }
+                }
+            }
+            let mut nodes_in_wave: Vec<&Node> =
+                wave.iter().map(|&index| nodes[index]).collect();
+            // Stable, so ties keep the input order.
+            nodes_in_wave.sort_by_key(|node| {
+                (
+                    priority(node).unwrap_or(u8::MAX),
+                    Reverse(node.blocks_count),
+                )
+            });
+            waves.push(nodes_in_wave);
+            next.sort_unstable();
+            wave = next;
+        }
+
+        if done < nodes.len() {
+            let blocked: Vec<bool> =
+                remaining.iter().map(|&count| count != 0).collect();
+            let cycles = find_cycles(&prerequisites, blocked)
+                .into_iter()
+                .map(|cycle| {
+                    cycle
+                        .into_iter()
+                        .map(|index| nodes[index].url.clone())
+                        .collect()
+                })
+                .collect();
+            return Err(GraphError::Cycles { cycles });
+        }
+
+        Ok(Self { waves })
+    }
+}
+
+/// Finds a cycle for each group of blocked nodes that block each other.
+/// Blocked nodes have a blocked prerequisite, so following prerequisites from
+/// any of them leads to a cycle.  Each cycle is in dependency order.
+fn find_cycles(
+    prerequisites: &[IndexSet<usize>],
+    mut blocked: Vec<bool>,
+) -> Vec<Vec<usize>> {
+    let mut cycles = Vec::new();
+    for start in 0..prerequisites.len() {
+        let mut path: IndexSet<usize> = IndexSet::new();
+        let mut current = start;
+        while blocked[current] {
+            if let Some(position) = path.get_index_of(&current) {
+                cycles.push(path.iter().skip(position).copied().collect());
+                break;
+            }
+            path.insert(current);
+            match prerequisites[current]
+                .iter()
+                .find(|&&prerequisite| blocked[prerequisite])
+            {
+                Some(&prerequisite) => current = prerequisite,
+                // Only blocked by cycles that were already found.
+                None => break,
+            }
+        }
+        for index in path {
+            blocked[index] = false;
+        }
+    }
+    cycles
+}
+
+/// Returns the priority from a label like "P1", "priority: high", or
+/// "priority/2", where 0 is the highest.
+fn priority(node: &Node) -> Option<u8> {
+    node.labels.iter().find_map(|label| {
+        let captures = regex!(
+            r"(?i-u)^(?:p|priority[ :/_-]*p?)([0-9])$|^priority[ :/_-]*(critical|urgent|high|medium|low)$"
+        )
+        .captures(label.trim())?;
+        if let Some(digit) = captures.get(1) {
+            return digit.as_str().parse().ok();
+        }
+        match captures.get(2)?.as_str().to_ascii_lowercase().as_str() {
+            "critical" | "urgent" => Some(0),
+            "high" => Some(1),
+            "medium" => Some(2),
+            "low" => Some(3),
+            _ => None,
+        }
+    })
+}
+
+/// Formats the waves as Markdown.
+impl std::fmt::Display for WorkOrder<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.waves.is_empty() {
+            return writeln!(f, "No open issues.");
+        }
+
+        for (index, wave) in self.waves.iter().enumerate() {
+            if index != 0 {
+                writeln!(f)?;
+            }
+            writeln!(f, "## Wave {}", index + 1)?;
+            writeln!(f)?;
+            if index == 0 {
+                writeln!(f, "Can start now.")?;
+            } else {
+                writeln!(f, "Can start after wave {index}.")?;
+            }
+            writeln!(f)?;
+            for (number, node) in wave.iter().enumerate() {
+                write!(f, "{}. {}", number + 1, markdown_link(node))?;
+                if node.blocks_count != 0 {
+                    write!(f, " (blocks {})", node.blocks_count)?;
+                }
+                writeln!(f)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::graph::test_util::{graph, issue, issue_url, label, with};
+
+    #[test]
+    fn test_waves() {
+        let labeled =
+            |issue, name| with(issue, json!({"labels": [label(name)]}));
+        let graph = graph([
+            issue(1, "OPEN", "Depends on: #2\nDepends on: #3"),
+            issue(2, "OPEN", "Depends on: #4"),
+            labeled(issue(3, "OPEN", "Depends on: #4"), "P1"),
+            issue(4, "CLOSED", ""),
+            issue(5, "OPEN", "Depends on: #1"),
+            labeled(issue(6, "OPEN", ""), "priority: high"),
+        ]);
+        let order = WorkOrder::new(&graph).unwrap();
+        let waves: Vec<Vec<&str>> = order
+            .waves
+            .iter()
+            .map(|wave| wave.iter().map(|node| node.text.as_str()).collect())
+            .collect();
+
+        assert_eq!(
+            waves,
+            [
+                vec!["Issue 3", "Issue 6", "Issue 2"],
+                vec!["Issue 1"],
+                vec!["Issue 5"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cycles() {
+        let graph = graph([
+            issue(1, "OPEN", "Depends on: #2"),
+            issue(2, "OPEN", "Depends on: #1"),
+            issue(3, "OPEN", "Depends on: #1\nDepends on: #4"),
+            issue(4, "OPEN", "Depends on: #4"),
+            issue(5, "OPEN", ""),
+        ]);
+        let error = WorkOrder::new(&graph).unwrap_err();
+
+        assert_eq!(
+            error,
+            GraphError::Cycles {
+                cycles: vec![
+                    vec![issue_url(1), issue_url(2)],
+                    vec![issue_url(4)]
+                ],
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "2 dependency cycles, where each item blocks the next:\n  {} -> {} -> {}\n  {} -> {}",
+                issue_url(2),
+                issue_url(1),
+                issue_url(2),
+                issue_url(4),
+                issue_url(4),
+            )
+        );
+    }
+}
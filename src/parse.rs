@@ -1,12 +1,18 @@
 use std::{borrow::Cow, iter::FusedIterator};
 
 use linkify::{LinkFinder, LinkKind};
+use regex::Regex;
 
+use crate::diagnostics::{Code, Diagnostics};
+use crate::url::{self, JIRA_URL_PREFIX};
 use crate::util::regex;
 
+/// Line prefixes that declare a dependency when none are configured.
+const DEFAULT_DEPENDS_ON_KEYWORDS: &[&str] = &["depends on"];
+
 /// A relation between two items.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub(crate) struct Relation<'a> {
+pub struct Relation<'a> {
     /// The kind of relation.
     pub kind: RelationKind,
     /// The URL of the related item.
@@ -14,28 +20,199 @@ pub(crate) struct Relation<'a> {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub(crate) enum RelationKind {
+pub enum RelationKind {
     DependsOn,
     TaskComplete,
     TaskIncomplete,
+    /// The target is a native sub-issue of this item.
+    SubIssue,
+    /// The target is the native parent issue of this item.
+    Parent,
+    /// The target is tracked by this item.
+    Tracks,
+    /// This item is tracked by the target.
+    TrackedBy,
+    /// This item is blocked by the target.
+    BlockedBy,
+    /// This item blocks the target.
+    Blocks,
+    /// This pull request closes the target issue when merged.
+    Closes,
+}
+
+impl RelationKind {
+    /// Returns true if the target depends on this item, rather than this item
+    /// depending on the target.
+    pub fn is_reversed(self) -> bool {
+        match self {
+            RelationKind::DependsOn
+            | RelationKind::TaskComplete
+            | RelationKind::TaskIncomplete
+            | RelationKind::SubIssue
+            | RelationKind::Tracks
+            | RelationKind::BlockedBy => false,
+            RelationKind::Parent
+            | RelationKind::TrackedBy
+            | RelationKind::Blocks
+            | RelationKind::Closes => true,
+        }
+    }
+}
+
+/// A keyword that declares a dependency is invalid.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum KeywordError {
+    /// The keyword is empty or only whitespace, so it would match every line.
+    Blank,
+    /// The keyword couldn't be compiled.
+    Regex(regex::Error),
+}
+
+impl std::fmt::Display for KeywordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeywordError::Blank => write!(f, "keywords can't be blank"),
+            KeywordError::Regex(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for KeywordError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            KeywordError::Blank => None,
+            KeywordError::Regex(error) => Some(error),
+        }
+    }
+}
+
+/// Options that control how references are recognized.
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    /// Hosts whose URLs are recognized in addition to github.com and the
+    /// item's own host, e.g. a GitHub Enterprise Server host like
+    /// "github.example.com".  The first one is also the host of
+    /// `owner/repo#123` references in items that aren't in a repository, like
+    /// draft issues and tasks.
+    pub hosts: Vec<String>,
+    /// The base URL of a Jira site, e.g. "https://example.atlassian.net".
+    /// When it's set, Jira issue keys like "PLAT-123" and links to the site's
+    /// issues are recognized.
+    pub jira_url: Option<String>,
+    /// Matches a line prefix that declares a dependency.
+    depends_on_prefix: Regex,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self::new(Vec::new(), &[]).expect("default keywords are valid")
+    }
 }
 
-pub(crate) fn relations<'t, 'r, 'c>(
+impl ParseOptions {
+    /// Creates options with the given additional hosts and keywords that
+    /// declare a dependency, e.g. "Depends on".  When no keywords are given,
+    /// "Depends on" is used.  Keywords can't be blank.
+    pub fn new(
+        hosts: Vec<String>,
+        depends_on_keywords: &[String],
+    ) -> Result<Self, KeywordError> {
+        if depends_on_keywords
+            .iter()
+            .any(|keyword| keyword.trim().is_empty())
+        {
+            return Err(KeywordError::Blank);
+        }
+        let keywords = if depends_on_keywords.is_empty() {
+            DEFAULT_DEPENDS_ON_KEYWORDS
+                .iter()
+                .map(|keyword| keyword_pattern(keyword))
+                .collect::<Vec<_>>()
+        } else {
+            depends_on_keywords
+                .iter()
+                .map(|keyword| keyword_pattern(keyword))
+                .collect()
+        };
+
+        // Matches the keyword, case-insensitive, an optional colon, and
+        // optional space before and after the colon.
+        let depends_on_prefix = Regex::new(&format!(
+            r"\A(?i-u)[[:space:]]*(?:{})[[:space:]]*:?[[:space:]]*",
+            keywords.join("|")
+        ))
+        .map_err(KeywordError::Regex)?;
+
+        Ok(Self {
+            hosts,
+            jira_url: None,
+            depends_on_prefix,
+        })
+    }
+
+    /// Returns the URL of a Jira issue, e.g.
+    /// "https://example.atlassian.net/browse/PLAT-123".  If there's no Jira
+    /// URL, a made-up one is returned; see [`JIRA_URL_PREFIX`].
+    pub fn jira_issue_url(&self, key: &str) -> String {
+        match &self.jira_url {
+            Some(base) => {
+                format!("{}/browse/{key}", base.trim().trim_end_matches('/'))
+            }
+            None => format!("{JIRA_URL_PREFIX}{key}"),
+        }
+    }
+
+    /// Returns true if the URL is of an issue on the Jira site.
+    fn is_jira_issue_url(&self, url: &str) -> bool {
+        self.jira_url.as_ref().is_some_and(|base| {
+            let base = url::canonical(base);
+            url::canonical(url).starts_with(&format!("{base}/browse/"))
+        })
+    }
+
+    /// Returns the scheme and host of `owner/repo#123` references in items
+    /// that aren't in a repository.
+    fn default_origin(&self) -> String {
+        let host = self.hosts.first().map_or("github.com", |host| host.trim());
+        format!("https://{host}")
+    }
+
+    /// Returns true if the URL is on github.com, the repository's host, or
+    /// one of the configured hosts.
+    fn is_known_host(&self, url: &str, repository: &str) -> bool {
+        let Some(host) = url_host(url) else {
+            return false;
+        };
+
+        host.eq_ignore_ascii_case("github.com")
+            || url_host(repository)
+                .is_some_and(|repo_host| host.eq_ignore_ascii_case(repo_host))
+            || self
+                .hosts
+                .iter()
+                .any(|known| host.eq_ignore_ascii_case(known))
+    }
+}
+
+/// Finds relations in an item's body text.  The context is used to identify
+/// the item in diagnostics, e.g. its URL.
+pub fn relations<'t, 'r, 'c, 'o, 'd>(
     text: &'t str,
     repository: &'r str,
     context: &'c str,
+    options: &'o ParseOptions,
+    diagnostics: &'d mut Diagnostics,
 ) -> impl FusedIterator<Item = Relation<'t>>
 where
     'r: 't,
     'c: 't,
+    'o: 't,
+    'd: 't,
 {
-    // Depends on link.  Matches "Depends on:", case-insensitive, an optional
-    // colon, and optional space before and after the colon.
-    let depends_on_prefix = regex!(
-        r"\A(?i-u)[[:space:]]*depends[[:space:]]+on[[:space:]]*:?[[:space:]]*"
-    );
+    let depends_on_prefix = &options.depends_on_prefix;
 
-    text.lines().filter_map(|line| {
+    text.lines().filter_map(move |line| {
         // Trim since it may be arbitrarily indented.
         let line = line.trim_start();
         if line.starts_with("- [ ]") || line.starts_with("- [x]") {
@@ -46,42 +223,91 @@ where
                 RelationKind::TaskComplete
             };
             let task_text = line["- [ ]".len()..].trim();
-            extract_url(task_text, repository)
+            extract_url(task_text, repository, options)
                 .map(|url| Relation { kind, target: url })
         } else if let Some(capture) = depends_on_prefix.find(line) {
             // Depends on link.  Remove "Depends on:", case-insensitive, with
             // optional space before and after the colon.
             let dep_text = &line[capture.end()..];
-            resolve_url(dep_text, repository, context).map(|url| Relation {
-                kind: RelationKind::DependsOn,
-                target: url,
-            })
+            resolve_url(dep_text, repository, context, options, diagnostics)
+                .map(|url| Relation {
+                    kind: RelationKind::DependsOn,
+                    target: url,
+                })
         } else {
             None
         }
     })
 }
 
+/// Finds issues that a pull request closes using GitHub's closing keywords,
+/// e.g. "Fixes #123".
+pub fn closing_references<'t, 'r, 'o>(
+    text: &'t str,
+    repository: &'r str,
+    options: &'o ParseOptions,
+) -> impl Iterator<Item = Relation<'t>>
+where
+    'r: 't,
+    'o: 't,
+{
+    // A closing keyword, an optional colon, and the reference that follows.
+    // https://docs.github.com/en/issues/tracking-your-work-with-issues/linking-a-pull-request-to-an-issue
+    let closing_keyword = regex!(
+        r"(?i-u:\b(?:close[sd]?|fix(?:e[sd])?|resolve[sd]?)[[:space:]]*:?[[:space:]]+)([^[:space:]]+)"
+    );
+
+    closing_keyword
+        .captures_iter(text)
+        .filter_map(move |captures| {
+            let (_, [reference]) = captures.extract();
+            extract_url(reference, repository, options).map(|url| Relation {
+                kind: RelationKind::Closes,
+                target: url,
+            })
+        })
+}
+
 /// Extract the issue URL from a string.  If a URL can't be found, a warning is
-/// printed.
+/// reported.
 fn resolve_url<'a>(
     text: &'a str,
     repository: &str,
     context: &str,
+    options: &ParseOptions,
+    diagnostics: &mut Diagnostics,
 ) -> Option<Cow<'a, str>> {
-    let url = extract_url(text, repository);
+    let url = extract_url(text, repository, options);
 
     if url.is_none() && !text.is_empty() {
-        eprintln!(
-            "Warning: Malformed issue or PR URL {text:?} in project item {context:?}"
-        );
+        let message = if repository.is_empty() && hash_number().is_match(text) {
+            format!(
+                "Can't resolve {text:?} outside of a repository; use owner/repo#123 or a URL"
+            )
+        } else {
+            format!("Malformed issue or PR URL {text:?}")
+        };
+        diagnostics.warn(Code::MalformedReference, message, Some(context));
     }
 
     url
 }
 
-/// Extract the issue URL from a string.
-fn extract_url<'a>(text: &'a str, repository: &str) -> Option<Cow<'a, str>> {
+/// Matches a GitHub issue or PR number, e.g. "#123".
+fn hash_number() -> &'static Regex {
+    // { beginning of string or {not repo characters} }
+    // # { number }
+    // { end of string or {not repo characters} }
+    regex!(r"(?:\A|[^0-9A-Za-z_-])#([0-9]+)(?:\z|[^0-9A-Za-z_-])")
+}
+
+/// Extract the issue URL from a string.  An empty repository means the text
+/// isn't in one, so only references that include the repository are found.
+pub(crate) fn extract_url<'a>(
+    text: &'a str,
+    repository: &str,
+    options: &ParseOptions,
+) -> Option<Cow<'a, str>> {
     if text.is_empty() {
         return None;
     }
@@ -89,37 +315,40 @@ fn extract_url<'a>(text: &'a str, repository: &str) -> Option<Cow<'a, str>> {
     // Note: Regular expressions use ASCII-only matching for speed.
 
     // Look for a GitHub issue or PR number.
-    //
-    // { beginning of string or {not repo characters} }
-    // # { number }
-    // { end of string or {not repo characters} }
-    let hash_number =
-        regex!(r"(?:\A|[^0-9A-Za-z_-])#([0-9]+)(?:\z|[^0-9A-Za-z_-])");
-    if let Some(captures) = hash_number.captures(text) {
+    if let Some(captures) = hash_number()
+        .captures(text)
+        .filter(|_| !repository.is_empty())
+    {
         let (_, [number]) = captures.extract();
         let url = format!("{repository}/issues/{number}");
         return Some(Cow::Owned(url));
     }
 
-    // Look for a GitHub owner/repo#number.
+    // Look for a GitHub owner/repo#number.  It's on the same host as the
+    // repository that references it, or the first configured host if it isn't
+    // in a repository.
     let owner_repo_number =
         regex!(r"(?-u:\b)([0-9A-Za-z_-]+)/([0-9A-Za-z_-]+)#([0-9]+)(?-u:\b)");
     if let Some(captures) = owner_repo_number.captures(text) {
         let (_, [owner, repo, number]) = captures.extract();
-        let url = format!("https://github.com/{owner}/{repo}/issues/{number}");
+        let origin = url_origin(repository)
+            .map_or_else(|| options.default_origin(), str::to_owned);
+        let url = format!("{origin}/{owner}/{repo}/issues/{number}");
         return Some(Cow::Owned(url));
     }
 
     // Look for a URL.  Use only the first one.
-    let is_github = regex!(r"\Ahttps?://github.com/");
     let finder = LinkFinder::new();
     for link in finder.links(text) {
         match link.kind() {
-            LinkKind::Url => {
-                if is_github.is_match(link.as_str()) {
-                    return Some(Cow::Borrowed(link.as_str()));
-                }
+            LinkKind::Url
+                if options.is_known_host(link.as_str(), repository)
+                    || options.is_jira_issue_url(link.as_str()) =>
+            {
+                return Some(Cow::Borrowed(link.as_str()));
             }
+            // Ignore non-GitHub links.
+            LinkKind::Url => {}
             // Ignore email links.
             LinkKind::Email => {}
             // Ignore other, future types of links.
@@ -127,9 +356,47 @@ fn extract_url<'a>(text: &'a str, repository: &str) -> Option<Cow<'a, str>> {
         }
     }
 
+    // Look for a Jira issue key at the start, e.g. "PLAT-123 Migrate", if
+    // there's a Jira site.  Elsewhere, words like "UTF-8" are too common.
+    if options.jira_url.is_some() {
+        let jira_key = regex!(
+            r"\A[[:space:]]*([A-Z][A-Z0-9_]+-[0-9]+)(?:\z|[^0-9A-Za-z_-])"
+        );
+        if let Some(captures) = jira_key.captures(text) {
+            let (_, [key]) = captures.extract();
+            return Some(Cow::Owned(options.jira_issue_url(key)));
+        }
+    }
+
     None
 }
 
+/// Converts a keyword to a pattern that matches it with any amount of space
+/// between words.
+fn keyword_pattern(keyword: &str) -> String {
+    keyword
+        .split_ascii_whitespace()
+        .map(regex::escape)
+        .collect::<Vec<_>>()
+        .join("[[:space:]]+")
+}
+
+/// Returns the scheme and host of a URL, e.g. "https://github.com".
+fn url_origin(url: &str) -> Option<&str> {
+    let (scheme, rest) = url.split_once("://")?;
+    let host_len = rest.find('/').unwrap_or(rest.len());
+    url.get(..scheme.len() + "://".len() + host_len)
+}
+
+/// Returns the host of an HTTP URL, e.g. "github.com".
+fn url_host(url: &str) -> Option<&str> {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))?;
+    let host = rest.split(['/', '?', '#']).next().unwrap_or(rest);
+    Some(host)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -139,58 +406,65 @@ mod tests {
         // Note: Option<Cow<str>> compares equal if the strings are equal,
         // regardless of whether it's owned or borrowed.
         let repository = "https://github.com/foo/bar";
+        let options = &ParseOptions::default();
 
         // Issue number.
         assert_eq!(
-            extract_url("#123", repository),
+            extract_url("#123", repository, options),
             Some(Cow::from("https://github.com/foo/bar/issues/123"))
         );
         // Issue number with leading space.
         assert_eq!(
-            extract_url(" #123", repository),
+            extract_url(" #123", repository, options),
             Some(Cow::from("https://github.com/foo/bar/issues/123"))
         );
         // Issue number with trailing space.
         assert_eq!(
-            extract_url("#123 ", repository),
+            extract_url("#123 ", repository, options),
             Some(Cow::from("https://github.com/foo/bar/issues/123"))
         );
         // Trailing period.
         assert_eq!(
-            extract_url("#123.", repository),
+            extract_url("#123.", repository, options),
             Some(Cow::from("https://github.com/foo/bar/issues/123"))
         );
         // Owner/repo#number.
         assert_eq!(
-            extract_url("aaa/bbb#123", repository),
+            extract_url("aaa/bbb#123", repository, options),
             Some(Cow::from("https://github.com/aaa/bbb/issues/123"))
         );
         // Owner/repo#number with leading space.
         assert_eq!(
-            extract_url(" aaa/bbb#123", repository),
+            extract_url(" aaa/bbb#123", repository, options),
             Some(Cow::from("https://github.com/aaa/bbb/issues/123"))
         );
         // Owner/repo#number with trailing space.
         assert_eq!(
-            extract_url("aaa/bbb#123 ", repository),
+            extract_url("aaa/bbb#123 ", repository, options),
             Some(Cow::from("https://github.com/aaa/bbb/issues/123"))
         );
         // Owner/repo#number with trailing period.
         assert_eq!(
-            extract_url("aaa/bbb#123.", repository),
+            extract_url("aaa/bbb#123.", repository, options),
             Some(Cow::from("https://github.com/aaa/bbb/issues/123"))
         );
         // Full URL.
-        let actual =
-            extract_url("https://github.com/aaa/bbb/issues/123", repository);
+        let actual = extract_url(
+            "https://github.com/aaa/bbb/issues/123",
+            repository,
+            options,
+        );
         assert_eq!(
             actual,
             Some(Cow::from("https://github.com/aaa/bbb/issues/123"))
         );
         assert!(matches!(actual, Some(Cow::Borrowed(_))));
         // Full URL with trailing period.
-        let actual =
-            extract_url("https://github.com/aaa/bbb/issues/123.", repository);
+        let actual = extract_url(
+            "https://github.com/aaa/bbb/issues/123.",
+            repository,
+            options,
+        );
         assert_eq!(
             actual,
             Some(Cow::from("https://github.com/aaa/bbb/issues/123"))
@@ -200,11 +474,269 @@ mod tests {
         let actual = extract_url(
             "[text](https://github.com/aaa/bbb/issues/123)",
             repository,
+            options,
         );
         assert_eq!(
             actual,
             Some(Cow::from("https://github.com/aaa/bbb/issues/123"))
         );
         assert!(matches!(actual, Some(Cow::Borrowed(_))));
+        // Other hosts are ignored.
+        assert_eq!(
+            extract_url(
+                "https://example.com/aaa/bbb/issues/123",
+                repository,
+                options
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_extract_url_enterprise() {
+        let repository = "https://ghe.example.com/foo/bar";
+        let options =
+            &ParseOptions::new(vec!["ghe2.example.com".to_owned()], &[])
+                .unwrap();
+
+        // Issue number.
+        assert_eq!(
+            extract_url("#123", repository, options),
+            Some(Cow::from("https://ghe.example.com/foo/bar/issues/123"))
+        );
+        // Owner/repo#number expands relative to the repository's host.
+        assert_eq!(
+            extract_url("aaa/bbb#123", repository, options),
+            Some(Cow::from("https://ghe.example.com/aaa/bbb/issues/123"))
+        );
+        // URL on the repository's own host.
+        assert_eq!(
+            extract_url(
+                "https://ghe.example.com/aaa/bbb/issues/123",
+                repository,
+                options
+            ),
+            Some(Cow::from("https://ghe.example.com/aaa/bbb/issues/123"))
+        );
+        // URL on a configured host.
+        assert_eq!(
+            extract_url(
+                "https://GHE2.example.com/aaa/bbb/issues/123",
+                repository,
+                options
+            ),
+            Some(Cow::from("https://GHE2.example.com/aaa/bbb/issues/123"))
+        );
+        // URL on github.com.
+        assert_eq!(
+            extract_url(
+                "https://github.com/aaa/bbb/issues/123",
+                repository,
+                options
+            ),
+            Some(Cow::from("https://github.com/aaa/bbb/issues/123"))
+        );
+        // URL on an unknown host.
+        assert_eq!(
+            extract_url(
+                "https://other.example.com/aaa/bbb/issues/123",
+                repository,
+                options
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_closing_references() {
+        let repository = "https://github.com/foo/bar";
+        let options = &ParseOptions::default();
+        let targets = |text| {
+            closing_references(text, repository, options)
+                .map(|relation| relation.target.into_owned())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            targets("Fixes #12"),
+            ["https://github.com/foo/bar/issues/12"]
+        );
+        assert_eq!(
+            targets("This closes: aaa/bbb#3.\nAlso resolved #4, and FIX #5"),
+            [
+                "https://github.com/aaa/bbb/issues/3",
+                "https://github.com/foo/bar/issues/4",
+                "https://github.com/foo/bar/issues/5",
+            ]
+        );
+        assert_eq!(
+            targets("Resolves https://github.com/aaa/bbb/issues/6"),
+            ["https://github.com/aaa/bbb/issues/6"]
+        );
+        // Not a keyword, or no reference.
+        assert!(targets("Prefixes #7").is_empty());
+        assert!(targets("This fixes the build").is_empty());
+    }
+
+    #[test]
+    fn test_relations_keywords() {
+        let repository = "https://github.com/foo/bar";
+        let text = "Depends on: #1\n  blocked   BY #2\n- [ ] #3\nRequires #4";
+        let targets = |options: &ParseOptions| {
+            relations(
+                text,
+                repository,
+                "context",
+                options,
+                &mut Diagnostics::new(),
+            )
+            .map(|relation| (relation.kind, relation.target.into_owned()))
+            .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            targets(&ParseOptions::default()),
+            [
+                (
+                    RelationKind::DependsOn,
+                    "https://github.com/foo/bar/issues/1".to_owned()
+                ),
+                (
+                    RelationKind::TaskIncomplete,
+                    "https://github.com/foo/bar/issues/3".to_owned()
+                ),
+            ]
+        );
+
+        let keywords = ["Blocked by".to_owned(), "requires".to_owned()];
+        let options = ParseOptions::new(Vec::new(), &keywords).unwrap();
+        assert_eq!(
+            targets(&options),
+            [
+                (
+                    RelationKind::DependsOn,
+                    "https://github.com/foo/bar/issues/2".to_owned()
+                ),
+                (
+                    RelationKind::TaskIncomplete,
+                    "https://github.com/foo/bar/issues/3".to_owned()
+                ),
+                (
+                    RelationKind::DependsOn,
+                    "https://github.com/foo/bar/issues/4".to_owned()
+                ),
+            ]
+        );
+
+        for blank in ["", " \t"] {
+            let keywords = ["Blocked by".to_owned(), blank.to_owned()];
+            assert!(matches!(
+                ParseOptions::new(Vec::new(), &keywords),
+                Err(KeywordError::Blank)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_relations_malformed() {
+        let mut diagnostics = Diagnostics::new();
+        let count = relations(
+            "Depends on: tomorrow\nDepends on: #1",
+            "https://github.com/foo/bar",
+            "https://github.com/foo/bar/issues/2",
+            &ParseOptions::default(),
+            &mut diagnostics,
+        )
+        .count();
+
+        assert_eq!(count, 1);
+        let diagnostics = diagnostics.iter().collect::<Vec<_>>();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, Code::MalformedReference);
+        assert_eq!(
+            diagnostics[0].item.as_deref(),
+            Some("https://github.com/foo/bar/issues/2")
+        );
+    }
+
+    #[test]
+    fn test_relations_outside_repository() {
+        let relations = |options: &ParseOptions| {
+            let mut diagnostics = Diagnostics::new();
+            let targets: Vec<_> = relations(
+                "Depends on: #1\nDepends on: aaa/bbb#2\n- [ ] #3",
+                "",
+                "draft:1",
+                options,
+                &mut diagnostics,
+            )
+            .map(|relation| relation.target.into_owned())
+            .collect();
+            let messages: Vec<_> = diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.message.clone())
+                .collect();
+            (targets, messages)
+        };
+
+        let (targets, messages) = relations(&ParseOptions::default());
+        assert_eq!(targets, ["https://github.com/aaa/bbb/issues/2"]);
+        assert_eq!(
+            messages,
+            [
+                r##"Can't resolve "#1" outside of a repository; use owner/repo#123 or a URL"##
+            ]
+        );
+
+        // The first configured host, e.g. GitHub Enterprise Server.
+        let options = ParseOptions::new(
+            vec!["ghe.example.com".to_owned(), "github.com".to_owned()],
+            &[],
+        )
+        .unwrap();
+        let (targets, _) = relations(&options);
+        assert_eq!(targets, ["https://ghe.example.com/aaa/bbb/issues/2"]);
+    }
+
+    #[test]
+    fn test_extract_url_jira() {
+        let repository = "https://github.com/foo/bar";
+        let mut options = ParseOptions::default();
+
+        // Not recognized without a Jira URL.
+        assert_eq!(extract_url("PLAT-123", repository, &options), None);
+
+        options.jira_url = Some("https://example.atlassian.net/".to_owned());
+        let expected =
+            Some(Cow::from("https://example.atlassian.net/browse/PLAT-123"));
+        assert_eq!(extract_url("PLAT-123", repository, &options), expected);
+        assert_eq!(
+            extract_url(" PLAT-123: Migrate", repository, &options),
+            expected
+        );
+        assert_eq!(
+            extract_url(
+                "See https://Example.atlassian.net/browse/PLAT-123",
+                repository,
+                &options
+            ),
+            Some(Cow::from("https://Example.atlassian.net/browse/PLAT-123"))
+        );
+        // Only at the start.
+        assert_eq!(
+            extract_url("Support UTF-8 in PLAT-123", repository, &options),
+            None
+        );
+        // Lowercase isn't a key.
+        assert_eq!(extract_url("plat-123", repository, &options), None);
+        // Links to other Jira sites aren't recognized.
+        assert_eq!(
+            extract_url(
+                "https://other.atlassian.net/browse/PLAT-123",
+                repository,
+                &options
+            ),
+            None
+        );
     }
 }
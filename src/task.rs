@@ -0,0 +1,120 @@
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+use crate::github::GithubIssueState;
+use crate::input;
+
+/// Work that isn't a GitHub issue, like a vendor contract or an approval.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Task {
+    /// Identifies the task in other tasks' `depends_on` and `blocks`.
+    pub id: String,
+    pub title: String,
+    /// "open" or "closed".  Default is "open".
+    #[serde(default)]
+    pub state: GithubIssueState,
+    /// Where the task is tracked, if anywhere.
+    #[serde(default)]
+    pub url: Option<String>,
+    /// What this depends on, as task IDs, `owner/repo#123` references, or
+    /// URLs.
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// What depends on this, in the same forms as `depends_on`.
+    #[serde(default)]
+    pub blocks: Vec<String>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TaskFile {
+    tasks: Vec<Task>,
+}
+
+/// Reads and concatenates the tasks in YAML or TOML files, which have a list
+/// named `tasks`.  Files ending in ".toml" are TOML, and others are YAML.
+/// Paths can be directories, where files ending in ".yaml", ".yml", or
+/// ".toml" are read, or glob patterns, as [`input::expand_paths`] says.  A
+/// path of "-" reads YAML from STDIN.
+pub fn read_tasks(paths: &[PathBuf]) -> Result<Vec<Task>> {
+    let mut tasks = Vec::new();
+
+    for path in input::expand_paths(paths, &["yaml", "yml", "toml"])? {
+        let text = input::read_input(&path)?;
+        let file = parse_tasks(&path, &text)?;
+        tasks.extend(file.tasks);
+    }
+
+    Ok(tasks)
+}
+
+fn parse_tasks(path: &Path, text: &str) -> Result<TaskFile> {
+    let is_toml = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("toml"));
+    if is_toml {
+        toml::from_str(text).map_err(|source| Error::Toml {
+            path: path.to_owned(),
+            source,
+        })
+    } else {
+        serde_yaml_ng::from_str(text).map_err(|source| Error::Yaml {
+            path: path.to_owned(),
+            source,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tasks() {
+        let yaml = "
+tasks:
+  - id: contract
+    title: Sign the vendor contract
+    url: https://example.com/contracts/1
+  - id: approval
+    title: Infra approval
+    state: closed
+    depends_on: [contract, foo/bar#1]
+    blocks: [https://github.com/foo/bar/issues/2]
+";
+        let toml = r#"
+[[tasks]]
+id = "contract"
+title = "Sign the vendor contract"
+url = "https://example.com/contracts/1"
+
+[[tasks]]
+id = "approval"
+title = "Infra approval"
+state = "closed"
+depends_on = ["contract", "foo/bar#1"]
+blocks = ["https://github.com/foo/bar/issues/2"]
+"#;
+        let from_yaml = parse_tasks(Path::new("tasks.yaml"), yaml).unwrap();
+        let from_toml = parse_tasks(Path::new("tasks.TOML"), toml).unwrap();
+        assert_eq!(from_yaml.tasks, from_toml.tasks);
+
+        let [contract, approval] = from_yaml.tasks.as_slice() else {
+            panic!("Expected 2 tasks: {:?}", from_yaml.tasks);
+        };
+        assert_eq!(contract.state, GithubIssueState::Open);
+        assert!(contract.depends_on.is_empty());
+        assert_eq!(approval.state, GithubIssueState::Closed);
+        assert_eq!(approval.url, None);
+        assert_eq!(approval.depends_on, ["contract", "foo/bar#1"]);
+
+        // A typo in a field name is an error rather than ignored.
+        let error = parse_tasks(
+            Path::new("tasks.yaml"),
+            "tasks:\n  - id: a\n    title: A\n    depend_on: [b]\n",
+        )
+        .unwrap_err();
+        assert!(matches!(error, Error::Yaml { .. }), "{error}");
+    }
+}
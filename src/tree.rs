@@ -0,0 +1,173 @@
+use std::collections::HashSet;
+
+use crate::graph::{Graph, Node};
+
+/// Which way to walk from the root.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Direction {
+    /// What the root depends on, transitively.
+    #[default]
+    Prerequisites,
+    /// What depends on the root, transitively.
+    Dependents,
+}
+
+/// Renders the transitive prerequisites or dependents of an item as an
+/// indented tree for a terminal.  A subtree that was already drawn is
+/// collapsed to one line that refers back to it.
+#[derive(Debug)]
+pub struct Tree<'g> {
+    graph: &'g Graph,
+    root: &'g Node,
+    direction: Direction,
+    /// Color items by state with ANSI escape codes.
+    pub color: bool,
+}
+
+const GREEN: &str = "\x1b[32m";
+const PURPLE: &str = "\x1b[35m";
+const DIM: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+impl<'g> Tree<'g> {
+    pub fn new(graph: &'g Graph, root: &'g Node, direction: Direction) -> Self {
+        Self {
+            graph,
+            root,
+            direction,
+            color: false,
+        }
+    }
+
+    fn children(&self, node: &'g Node) -> Vec<&'g Node> {
+        match self.direction {
+            Direction::Prerequisites => self.graph.dependencies(node).collect(),
+            Direction::Dependents => self.graph.dependents(node).collect(),
+        }
+    }
+
+    fn fmt_node(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        node: &Node,
+    ) -> std::fmt::Result {
+        let (state, color) = if node.is_open() {
+            ("open", GREEN)
+        } else {
+            ("closed", PURPLE)
+        };
+        if self.color {
+            write!(f, "{color}{} {}{RESET}", node.reference(), node.text)?;
+            write!(f, " {DIM}({state}){RESET}")
+        } else {
+            write!(f, "{} {} ({state})", node.reference(), node.text)
+        }
+    }
+
+    fn fmt_children(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        node: &'g Node,
+        prefix: &str,
+        path: &mut Vec<&'g str>,
+        expanded: &mut HashSet<&'g str>,
+    ) -> std::fmt::Result {
+        let children = self.children(node);
+        let count = children.len();
+        for (index, child) in children.into_iter().enumerate() {
+            let last = index + 1 == count;
+            let (branch, indent) = if last {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+            write!(f, "{prefix}{branch}")?;
+            self.fmt_node(f, child)?;
+
+            let note = if path.contains(&child.url.as_str()) {
+                Some("cycle")
+            } else if expanded.contains(child.url.as_str())
+                && !self.children(child).is_empty()
+            {
+                Some("see above")
+            } else {
+                None
+            };
+            if let Some(note) = note {
+                if self.color {
+                    writeln!(f, " {DIM}↑ {note}{RESET}")?;
+                } else {
+                    writeln!(f, " ↑ {note}")?;
+                }
+                continue;
+            }
+            writeln!(f)?;
+
+            expanded.insert(&child.url);
+            path.push(&child.url);
+            self.fmt_children(
+                f,
+                child,
+                &format!("{prefix}{indent}"),
+                path,
+                expanded,
+            )?;
+            path.pop();
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Tree<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_node(f, self.root)?;
+        writeln!(f)?;
+        let mut path = vec![self.root.url.as_str()];
+        let mut expanded = HashSet::from([self.root.url.as_str()]);
+        self.fmt_children(f, self.root, "", &mut path, &mut expanded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::test_util::{graph, issue};
+
+    #[test]
+    fn test_tree() {
+        let graph = graph([
+            issue(1, "OPEN", "Depends on: #2\nDepends on: #3"),
+            issue(2, "OPEN", "Depends on: #4"),
+            issue(3, "OPEN", "Depends on: #4\nDepends on: #1"),
+            issue(4, "CLOSED", "Depends on: #5"),
+            issue(5, "CLOSED", ""),
+        ]);
+        let root = graph.find("#1").unwrap();
+
+        assert_eq!(
+            Tree::new(&graph, root, Direction::Prerequisites).to_string(),
+            "\
+foo/bar#1 Issue 1 (open)
+├── foo/bar#2 Issue 2 (open)
+│   └── foo/bar#4 Issue 4 (closed)
+│       └── foo/bar#5 Issue 5 (closed)
+└── foo/bar#3 Issue 3 (open)
+    ├── foo/bar#4 Issue 4 (closed) ↑ see above
+    └── foo/bar#1 Issue 1 (open) ↑ cycle
+"
+        );
+        let root = graph.find("foo/bar#5").unwrap();
+        assert_eq!(
+            Tree::new(&graph, root, Direction::Dependents).to_string(),
+            "\
+foo/bar#5 Issue 5 (closed)
+└── foo/bar#4 Issue 4 (closed)
+    ├── foo/bar#2 Issue 2 (open)
+    │   └── foo/bar#1 Issue 1 (open)
+    │       └── foo/bar#3 Issue 3 (open)
+    │           └── foo/bar#1 Issue 1 (open) ↑ cycle
+    └── foo/bar#3 Issue 3 (open) ↑ see above
+"
+        );
+    }
+}
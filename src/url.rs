@@ -0,0 +1,168 @@
+use crate::util::regex;
+
+/// The prefix of the URL that's made up for draft issues, which don't have
+/// one, e.g. "draft:PVTI_lADOAB".
+pub const DRAFT_URL_PREFIX: &str = "draft:";
+
+/// The prefix of the URL that's made up for tasks from task files that don't
+/// have one, e.g. "task:vendor-contract".
+pub const TASK_URL_PREFIX: &str = "task:";
+
+/// The prefix of the URL that's made up for Jira issues when there's no Jira
+/// URL, e.g. "jira:PLAT-123".
+pub const JIRA_URL_PREFIX: &str = "jira:";
+
+/// Returns the canonical form of an issue or PR URL so that every spelling of
+/// a reference maps to the same node.
+///
+/// - The scheme becomes `https` and the host is lowercased, without `www.`.
+/// - Trailing slashes are removed from the path.
+/// - Issue and PR paths become `/{owner}/{repo}/issues/{number}` with the
+///   owner and repo lowercased, since GitHub treats them case-insensitively
+///   and issues and PRs share the same numbers.  Their query strings and
+///   fragments, e.g. links to comments, are removed.  Other URLs keep theirs,
+///   since they can be part of what the URL refers to.
+///
+/// Text that isn't an HTTP URL is returned unchanged.
+pub fn canonical(url: &str) -> String {
+    let url = url.trim();
+    let Some((scheme, rest)) = url.split_once("://") else {
+        return url.to_owned();
+    };
+    if !scheme.eq_ignore_ascii_case("https")
+        && !scheme.eq_ignore_ascii_case("http")
+    {
+        return url.to_owned();
+    }
+
+    // Split off the query and fragment.
+    let (rest, suffix) = rest
+        .find(['?', '#'])
+        .map_or((rest, ""), |index| rest.split_at(index));
+
+    let (host, path) = rest.split_once('/').unwrap_or((rest, ""));
+    let host = host.to_ascii_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    let path = path.trim_end_matches('/');
+
+    // { owner } / { repo } / { issues, pull, or pulls } / { number } { rest }
+    let issue_path =
+        regex!(r"\A([^/]+)/([^/]+)/(?:issues|pulls?)/([0-9]+)(?:/.*)?\z");
+    if let Some(captures) = issue_path.captures(path) {
+        let (_, [owner, repo, number]) = captures.extract();
+        let owner = owner.to_ascii_lowercase();
+        let repo = repo.to_ascii_lowercase();
+        return format!("https://{host}/{owner}/{repo}/issues/{number}");
+    }
+
+    if path.is_empty() {
+        format!("https://{host}{suffix}")
+    } else {
+        format!("https://{host}/{path}{suffix}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical() {
+        let expected = "https://github.com/foo/bar/issues/5";
+
+        // Already canonical.
+        assert_eq!(canonical("https://github.com/foo/bar/issues/5"), expected);
+        // PR path.
+        assert_eq!(canonical("https://github.com/foo/bar/pull/5"), expected);
+        assert_eq!(canonical("https://github.com/foo/bar/pulls/5"), expected);
+        // PR sub-page.
+        assert_eq!(
+            canonical("https://github.com/foo/bar/pull/5/files"),
+            expected
+        );
+        // HTTP scheme.
+        assert_eq!(canonical("http://github.com/foo/bar/issues/5"), expected);
+        // Uppercase scheme.
+        assert_eq!(canonical("HTTPS://github.com/foo/bar/issues/5"), expected);
+        // Mixed-case host.
+        assert_eq!(canonical("https://GitHub.com/foo/bar/issues/5"), expected);
+        // www prefix.
+        assert_eq!(
+            canonical("https://www.github.com/foo/bar/issues/5"),
+            expected
+        );
+        // Mixed-case owner and repo.
+        assert_eq!(canonical("https://github.com/Foo/BAR/issues/5"), expected);
+        // Trailing slash.
+        assert_eq!(canonical("https://github.com/foo/bar/issues/5/"), expected);
+        assert_eq!(
+            canonical("https://github.com/foo/bar/issues/5//"),
+            expected
+        );
+        // Comment fragment.
+        assert_eq!(
+            canonical(
+                "https://github.com/foo/bar/issues/5#issuecomment-123456"
+            ),
+            expected
+        );
+        // Query string.
+        assert_eq!(
+            canonical("https://github.com/foo/bar/issues/5?q=is%3Aopen"),
+            expected
+        );
+        // Query string and fragment.
+        assert_eq!(
+            canonical("https://github.com/foo/bar/pull/5/?a=b#discussion_r1"),
+            expected
+        );
+        // Surrounding whitespace.
+        assert_eq!(
+            canonical(" https://github.com/foo/bar/issues/5\n"),
+            expected
+        );
+        // Everything at once.
+        assert_eq!(
+            canonical("http://WWW.GitHub.com/Foo/Bar/pull/5/commits/?x#y"),
+            expected
+        );
+
+        // Other hosts keep their issue paths.
+        assert_eq!(
+            canonical("http://GHE.Example.com/Foo/Bar/pull/5"),
+            "https://ghe.example.com/foo/bar/issues/5"
+        );
+        // Non-issue URLs keep their path case, query string, and fragment.
+        assert_eq!(
+            canonical("http://Jira.Example.com/browse/PLAT-1/"),
+            "https://jira.example.com/browse/PLAT-1"
+        );
+        assert_eq!(
+            canonical("https://Example.com/view/?id=1#top"),
+            "https://example.com/view?id=1#top"
+        );
+        assert_eq!(
+            canonical("https://example.com/view?id=2"),
+            "https://example.com/view?id=2"
+        );
+        assert_eq!(
+            canonical("https://Example.com?id=1"),
+            "https://example.com?id=1"
+        );
+        assert_eq!(
+            canonical("https://github.com/orgs/Foo/projects/1/"),
+            "https://github.com/orgs/Foo/projects/1"
+        );
+        // Bare host.
+        assert_eq!(canonical("https://GitHub.com/"), "https://github.com");
+        // Not an issue number.
+        assert_eq!(
+            canonical("https://github.com/foo/bar/issues/new"),
+            "https://github.com/foo/bar/issues/new"
+        );
+        // Not an HTTP URL.
+        assert_eq!(canonical("draft:123"), "draft:123");
+        assert_eq!(canonical("ftp://Example.com/A/"), "ftp://Example.com/A/");
+        assert_eq!(canonical(""), "");
+    }
+}
@@ -9,3 +9,16 @@ macro_rules! regex {
 
 // Export the macro.
 pub(crate) use regex;
+
+/// Formats a Markdown link to the node, e.g. `[Title](url)`.
+pub(crate) fn markdown_link(node: &crate::graph::Node) -> String {
+    let text = node
+        .text
+        .replace('\\', "\\\\")
+        .replace('[', "\\[")
+        .replace(']', "\\]");
+    match node.link() {
+        Some(link) => format!("[{text}]({link})"),
+        None => text,
+    }
+}
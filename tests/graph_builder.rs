@@ -0,0 +1,68 @@
+//! Builds a graph through the library's public API, as another tool would.
+
+use techtree::chart::{Filter, Flowchart};
+use techtree::diagnostics::{Code, Diagnostics};
+use techtree::github::{GithubIssue, GithubPullRequest};
+use techtree::graph::GraphBuilder;
+use techtree::parse::ParseOptions;
+
+const ISSUES: &str = r#"[
+    {"body": "Depends on: #2\nDepends on: nothing", "closed": false,
+     "id": "1", "labels": [], "number": 1, "projectItems": [],
+     "state": "OPEN", "title": "Ship it", "updatedAt": "2024-01-01T00:00:00Z",
+     "url": "https://github.com/Foo/Bar/issues/1"},
+    {"body": "", "closed": true, "id": "2", "labels": [], "number": 2,
+     "projectItems": [], "state": "CLOSED", "title": "Write it",
+     "updatedAt": "2024-01-01T00:00:00Z",
+     "url": "https://github.com/foo/bar/issues/2"}
+]"#;
+
+const PULL_REQUESTS: &str = r#"[
+    {"body": "Fixes #1", "id": "3", "number": 3, "state": "OPEN",
+     "title": "Ship", "updatedAt": "2024-01-01T00:00:00Z",
+     "url": "https://github.com/foo/bar/pull/3"}
+]"#;
+
+#[test]
+fn test_graph_builder() {
+    let issues: Vec<GithubIssue> = serde_json::from_str(ISSUES).unwrap();
+    let pull_requests: Vec<GithubPullRequest> =
+        serde_json::from_str(PULL_REQUESTS).unwrap();
+    let options = ParseOptions::default();
+    let mut diagnostics = Diagnostics::new();
+    let mut builder = GraphBuilder::new(&options, &mut diagnostics);
+    for issue in issues {
+        builder.add_issue(issue);
+    }
+    for pull_request in pull_requests {
+        builder.add_pull_request(pull_request);
+    }
+    let graph = builder.build();
+
+    assert_eq!(graph.nodes.len(), 3);
+    // Any spelling of the URL finds the node.
+    let one = graph.get("http://github.com/foo/bar/issues/1#top").unwrap();
+    let two = graph.find("foo/bar#2").unwrap();
+    let dependencies: Vec<&str> = graph
+        .dependencies(one)
+        .map(|node| node.url.as_str())
+        .collect();
+    assert_eq!(dependencies, [two.url.as_str()]);
+    assert!(!graph.is_blocked(one));
+    assert_eq!(two.blocks_count, 1);
+
+    // Problems are collected rather than printed.
+    let codes: Vec<Code> = diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.code)
+        .collect();
+    assert_eq!(codes, [Code::MalformedReference]);
+    assert_eq!(
+        diagnostics.iter().next().unwrap().item.as_deref(),
+        Some("https://github.com/Foo/Bar/issues/1")
+    );
+
+    let chart = Flowchart::new(&graph, String::new(), true, Filter::default())
+        .to_string();
+    assert!(chart.contains("Ship it"), "{chart}");
+}